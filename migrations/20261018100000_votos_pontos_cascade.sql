@@ -0,0 +1,5 @@
+-- permite remover um ponto (DELETE /pontos/{uid}) levando junto os votos
+ALTER TABLE votos_pontos
+DROP CONSTRAINT votos_pontos_ponto_id_fkey,
+ADD CONSTRAINT votos_pontos_ponto_id_fkey
+    FOREIGN KEY (ponto_id) REFERENCES pontos(uid) ON DELETE CASCADE;
@@ -0,0 +1,13 @@
+CREATE TABLE public.solicitacoes_historico(
+    UID VARCHAR primary key not null default gen_random_uuid()::VARCHAR,
+    SOLICITACAO_ID VARCHAR not null references solicitacoes(uid) ON DELETE CASCADE,
+    APP_ID VARCHAR not null references APP(UID),
+    status_anterior varchar not null,
+    status_novo varchar not null,
+    observacao varchar,
+    alterado_por varchar not null, -- uid do app ou id do usuario
+    alterado_por_tipo varchar not null, -- App, User
+    CREATED_AT TIMESTAMPTZ not null default CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_solicitacoes_historico_solicitacao ON solicitacoes_historico (solicitacao_id, created_at);
@@ -0,0 +1,18 @@
+alter table pontos add latitude double precision;
+alter table pontos add longitude double precision;
+
+-- aproveita o gps ja cadastrado quando estiver no formato "lat,lon"
+update pontos set latitude = c.lat, longitude = c.lon
+from (
+    select uid,
+        case when gps ~ '^\s*-?\d+(\.\d+)?\s*,\s*-?\d+(\.\d+)?\s*$'
+            then split_part(gps, ',', 1)::double precision end as lat,
+        case when gps ~ '^\s*-?\d+(\.\d+)?\s*,\s*-?\d+(\.\d+)?\s*$'
+            then split_part(gps, ',', 2)::double precision end as lon
+    from pontos
+) c
+where c.uid = pontos.uid
+  and c.lat between -90 and 90
+  and c.lon between -180 and 180;
+
+CREATE INDEX idx_pontos_latitude_longitude ON pontos (latitude, longitude) WHERE latitude IS NOT NULL;
@@ -0,0 +1,23 @@
+-- refresh tokens rotativos; cada login abre uma sessao (sessao_id) e cada
+-- renovacao gera uma nova linha na mesma sessao, revogando a anterior
+CREATE TABLE public.refresh_tokens(
+    UID VARCHAR primary key not null default gen_random_uuid()::VARCHAR,
+    USER_ID VARCHAR not null references users(id) ON DELETE CASCADE,
+    SESSAO_ID VARCHAR not null,
+    token_hash text not null,
+    access_jti varchar not null, -- jti do access token emitido junto
+    expires_at TIMESTAMPTZ not null,
+    revoked_at TIMESTAMPTZ,
+    CREATED_AT TIMESTAMPTZ not null default CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_refresh_tokens_user_id ON refresh_tokens (user_id);
+CREATE INDEX idx_refresh_tokens_sessao_id ON refresh_tokens (sessao_id);
+
+-- access tokens revogados antes de expirar (logout), consultados no middleware
+CREATE TABLE public.tokens_revogados(
+    JTI VARCHAR primary key not null,
+    USER_ID VARCHAR not null references users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ not null,
+    CREATED_AT TIMESTAMPTZ not null default CURRENT_TIMESTAMP
+);
@@ -0,0 +1,15 @@
+-- chaves de API dos aplicativos, substituem o uid do app no header x-emergencial-id
+-- publishable: somente leitura, pode ir no frontend publico
+-- secret: leitura e escrita, somente em backend
+CREATE TABLE public.app_keys(
+    UID VARCHAR primary key not null default gen_random_uuid()::VARCHAR,
+    APP_ID VARCHAR not null references APP(UID),
+    label varchar not null,
+    tipo varchar not null check (tipo in ('publishable', 'secret')),
+    key_hash text not null,
+    CREATED_AT TIMESTAMPTZ not null default CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_app_keys_app_id ON app_keys (app_id);
@@ -0,0 +1,14 @@
+-- papel de cada usuario dentro de um app (controle de acesso por rota)
+CREATE TABLE public.papeis_usuarios(
+    USER_ID VARCHAR not null references users(id) ON DELETE CASCADE,
+    APP_ID VARCHAR not null references APP(UID),
+    papel varchar not null check (papel in ('ADMIN', 'COORDENADOR', 'VOLUNTARIO', 'VISUALIZADOR')),
+    CREATED_AT TIMESTAMPTZ not null default CURRENT_TIMESTAMP,
+    created_by VARCHAR,
+    primary key (user_id, app_id)
+);
+
+CREATE INDEX idx_papeis_usuarios_app_id ON papeis_usuarios (app_id);
+
+-- app escolhido no login, mantido nas renovacoes da sessao
+ALTER TABLE refresh_tokens ADD COLUMN app_id VARCHAR;
@@ -0,0 +1,6 @@
+-- super administradores cadastram e gerenciam apps (tenants)
+ALTER TABLE users ADD COLUMN super_admin boolean not null default false;
+
+-- apps desativados nao autenticam chaves nem aceitam sessoes
+ALTER TABLE app ADD COLUMN ativo boolean not null default true;
+ALTER TABLE app ADD COLUMN updated_at TIMESTAMPTZ;
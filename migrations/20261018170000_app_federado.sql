@@ -0,0 +1,3 @@
+-- apps publicos podem compartilhar seus dados na visao federada (?federado=true)
+-- o compartilhamento e opcional e so vale para tipo = 'public'
+ALTER TABLE app ADD COLUMN federado boolean not null default false;
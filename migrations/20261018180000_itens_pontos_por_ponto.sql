@@ -0,0 +1,42 @@
+-- estoque por ponto de coleta: a chave passa de (app_id, item) para (ponto_id, item)
+ALTER TABLE itens_pontos DROP CONSTRAINT itens_pontos_pkey;
+
+ALTER TABLE itens_pontos
+ADD COLUMN ponto_id VARCHAR references pontos(uid) ON DELETE CASCADE;
+
+-- linhas antigas vao para o unico ponto do app, quando ele tem so um
+UPDATE itens_pontos SET ponto_id = unico.uid
+FROM (
+    SELECT app_id, min(uid) AS uid FROM pontos GROUP BY app_id HAVING count(*) = 1
+) unico
+WHERE unico.app_id = itens_pontos.app_id;
+
+-- nos demais casos nao ha como saber o ponto: as linhas ficam guardadas em
+-- itens_pontos_legado para o app recadastrar o estoque no ponto certo
+CREATE TABLE itens_pontos_legado AS
+SELECT app_id, item, aceitando, recusando, estoque, updated_at
+FROM itens_pontos WHERE ponto_id IS NULL;
+
+DO $$
+DECLARE
+    sem_ponto INTEGER;
+BEGIN
+    SELECT count(*) INTO sem_ponto FROM itens_pontos_legado;
+    IF sem_ponto > 0 THEN
+        RAISE NOTICE '% linha(s) de itens_pontos sem ponto definido movidas para itens_pontos_legado', sem_ponto;
+    END IF;
+END $$;
+
+DELETE FROM itens_pontos WHERE ponto_id IS NULL;
+
+ALTER TABLE itens_pontos ALTER COLUMN ponto_id SET NOT NULL;
+
+-- abaixo deste estoque o ponto precisa receber o item
+ALTER TABLE itens_pontos ADD COLUMN estoque_minimo INTEGER;
+
+ALTER TABLE itens_pontos ALTER COLUMN updated_at SET DEFAULT now();
+
+ALTER TABLE itens_pontos
+ADD PRIMARY KEY (ponto_id, item);
+
+CREATE INDEX idx_itens_pontos_app_id ON itens_pontos (app_id);
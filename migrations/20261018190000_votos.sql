@@ -0,0 +1,19 @@
+-- contadores de votos (confirmacoes da comunidade), mantidos pelos endpoints de voto
+ALTER TABLE pontos ADD COLUMN votos integer not null default 0;
+
+UPDATE pontos SET votos = (SELECT count(*) FROM votos_pontos WHERE votos_pontos.ponto_id = pontos.uid);
+
+UPDATE solicitacoes SET votos = 0 WHERE votos IS NULL;
+
+ALTER TABLE solicitacoes
+ALTER COLUMN votos SET DEFAULT 0,
+ALTER COLUMN votos SET NOT NULL;
+
+-- um voto por usuario em cada solicitacao
+CREATE TABLE public.votos_solicitacoes(
+    APP_ID VARCHAR not null references APP(UID),
+    SOLICITACAO_ID VARCHAR not null references solicitacoes(uid) ON DELETE CASCADE,
+    USER_ID VARCHAR not null,
+    CREATED_AT TIMESTAMPTZ not null default CURRENT_TIMESTAMP,
+    primary key (app_id, solicitacao_id, user_id)
+);
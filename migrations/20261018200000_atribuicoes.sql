@@ -0,0 +1,19 @@
+-- voluntario designado para atender uma solicitacao
+CREATE TABLE public.atribuicoes(
+    UID VARCHAR primary key not null default gen_random_uuid()::VARCHAR,
+    APP_ID VARCHAR not null references APP(UID),
+    VOLUNTARIO_ID VARCHAR not null references voluntarios(uid) ON DELETE CASCADE,
+    SOLICITACAO_ID VARCHAR references solicitacoes(uid) ON DELETE CASCADE,
+    status varchar not null default 'PENDENTE',
+    observacao varchar,
+    created_by VARCHAR,
+    CREATED_AT TIMESTAMPTZ not null default CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_atribuicoes_voluntario_id ON atribuicoes (voluntario_id);
+CREATE INDEX idx_atribuicoes_solicitacao_id ON atribuicoes (solicitacao_id);
+
+-- o mesmo voluntario nao pode ter duas atribuicoes em aberto para o mesmo pedido
+CREATE UNIQUE INDEX uq_atribuicoes_solicitacao_voluntario_ativa
+    ON atribuicoes (solicitacao_id, voluntario_id)
+    WHERE status IN ('PENDENTE', 'ACEITA', 'EM_ANDAMENTO');
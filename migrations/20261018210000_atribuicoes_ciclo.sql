@@ -0,0 +1,27 @@
+-- atribuicoes tambem para turnos em pontos, com o ciclo de vida e seus horarios
+ALTER TABLE atribuicoes
+ADD COLUMN ponto_id VARCHAR references pontos(uid) ON DELETE CASCADE,
+ADD COLUMN turno_inicio TIMESTAMPTZ,
+ADD COLUMN turno_fim TIMESTAMPTZ,
+ADD COLUMN aceita_em TIMESTAMPTZ,
+ADD COLUMN recusada_em TIMESTAMPTZ,
+ADD COLUMN iniciada_em TIMESTAMPTZ,
+ADD COLUMN concluida_em TIMESTAMPTZ,
+ADD COLUMN cancelada_em TIMESTAMPTZ,
+ADD COLUMN updated_at TIMESTAMPTZ;
+
+-- PENDENTE -> ACEITA -> EM_ANDAMENTO -> CONCLUIDA, ou RECUSADA / CANCELADA
+ALTER TABLE atribuicoes
+ADD CONSTRAINT atribuicoes_status_check
+    CHECK (status IN ('PENDENTE', 'ACEITA', 'RECUSADA', 'EM_ANDAMENTO', 'CONCLUIDA', 'CANCELADA'));
+
+-- cada atribuicao e para um pedido ou para um turno em um ponto
+ALTER TABLE atribuicoes
+ADD CONSTRAINT atribuicoes_alvo_check
+    CHECK ((solicitacao_id IS NULL) <> (ponto_id IS NULL));
+
+ALTER TABLE atribuicoes
+ADD CONSTRAINT atribuicoes_turno_check
+    CHECK (turno_fim IS NULL OR turno_inicio IS NULL OR turno_fim > turno_inicio);
+
+CREATE INDEX idx_atribuicoes_ponto_id ON atribuicoes (ponto_id);
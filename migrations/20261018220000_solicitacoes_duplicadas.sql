@@ -0,0 +1,16 @@
+-- deteccao de pedidos duplicados (mesma familia pedindo por varios apps)
+ALTER TABLE solicitacoes
+ADD COLUMN telefone_normalizado VARCHAR,
+ADD COLUMN duplicada_de VARCHAR references solicitacoes(uid) ON DELETE SET NULL,
+ADD COLUMN mesclada_em TIMESTAMPTZ;
+
+-- somente digitos, sem o 55 do pais e sem o 0 de discagem (mesma regra de duplicatas.rs)
+UPDATE solicitacoes SET telefone_normalizado = ltrim(
+    CASE
+        WHEN regexp_replace(telefone, '\D', '', 'g') LIKE '55%' AND length(regexp_replace(telefone, '\D', '', 'g')) >= 12
+            THEN substr(regexp_replace(telefone, '\D', '', 'g'), 3)
+        ELSE regexp_replace(telefone, '\D', '', 'g')
+    END, '0');
+
+CREATE INDEX idx_solicitacoes_telefone_normalizado ON solicitacoes (telefone_normalizado, created_at);
+CREATE INDEX idx_solicitacoes_duplicada_de ON solicitacoes (duplicada_de);
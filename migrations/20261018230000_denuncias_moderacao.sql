@@ -0,0 +1,26 @@
+-- triagem das denuncias pelos moderadores (coordenadores do app)
+ALTER TABLE denuncias
+ADD COLUMN status VARCHAR not null default 'RECEBIDA',
+ADD COLUMN moderado_em TIMESTAMPTZ;
+
+ALTER TABLE denuncias
+ADD CONSTRAINT denuncias_status_check
+    CHECK (status IN ('RECEBIDA', 'EM_ANALISE', 'CONFIRMADA', 'DESCARTADA'));
+
+-- notas dos moderadores a cada mudanca de status
+CREATE TABLE public.denuncias_moderacao(
+    UID VARCHAR primary key not null default gen_random_uuid()::VARCHAR,
+    DENUNCIA_ID VARCHAR not null references denuncias(uid) ON DELETE CASCADE,
+    APP_ID VARCHAR not null references APP(UID),
+    status_anterior varchar not null,
+    status_novo varchar not null,
+    nota varchar,
+    moderado_por varchar not null, -- uid do app ou id do usuario
+    moderado_por_tipo varchar not null, -- App, User
+    CREATED_AT TIMESTAMPTZ not null default CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_denuncias_moderacao_denuncia ON denuncias_moderacao (denuncia_id, created_at);
+
+-- consultas publicas da lista de bloqueio
+CREATE INDEX idx_denuncias_chave_pix_confirmada ON denuncias (lower(trim(chave_pix))) WHERE status = 'CONFIRMADA';
@@ -0,0 +1,25 @@
+-- mesma chave escrita de formas diferentes (maiusculas, pontuacao de cpf/cnpj/telefone)
+CREATE FUNCTION normalizar_chave_pix(chave text) RETURNS text
+LANGUAGE sql IMMUTABLE AS $$
+    SELECT CASE
+        WHEN trim(chave) ~ '^[0-9 .()/+-]+$' THEN regexp_replace(chave, '\D', '', 'g')
+        ELSE lower(trim(chave))
+    END
+$$;
+
+-- ponto em quarentena por usar chave pix/cnpj de golpe confirmado
+ALTER TABLE pontos
+ADD COLUMN bloqueado_em TIMESTAMPTZ,
+ADD COLUMN denuncia_bloqueio VARCHAR references denuncias(uid) ON DELETE SET NULL;
+
+DROP INDEX idx_denuncias_chave_pix_confirmada;
+CREATE INDEX idx_denuncias_chave_pix_confirmada ON denuncias (normalizar_chave_pix(chave_pix)) WHERE status = 'CONFIRMADA';
+CREATE INDEX idx_pontos_pix_normalizado ON pontos (normalizar_chave_pix(pix));
+CREATE INDEX idx_pontos_cnpj_normalizado ON pontos (normalizar_chave_pix(cnpj));
+
+-- denuncias ja confirmadas
+UPDATE pontos SET bloqueado_em = now(), denuncia_bloqueio = denuncias.uid
+FROM denuncias
+WHERE denuncias.status = 'CONFIRMADA'
+  AND normalizar_chave_pix(denuncias.chave_pix) <> ''
+  AND normalizar_chave_pix(denuncias.chave_pix) IN (normalizar_chave_pix(pontos.pix), normalizar_chave_pix(pontos.cnpj));
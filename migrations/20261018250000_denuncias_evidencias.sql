@@ -0,0 +1,14 @@
+-- arquivos de evidencia das denuncias (imagens e pdf); o conteudo fica no armazenamento configurado
+CREATE TABLE public.denuncias_evidencias(
+    UID VARCHAR primary key not null default gen_random_uuid()::VARCHAR,
+    DENUNCIA_ID VARCHAR not null references denuncias(uid) ON DELETE CASCADE,
+    APP_ID VARCHAR not null references APP(UID),
+    nome_arquivo varchar not null,
+    mime varchar not null,
+    tamanho bigint not null,
+    sha256 varchar not null,
+    chave_armazenamento varchar not null,
+    created_by varchar,
+    CREATED_AT TIMESTAMPTZ not null default CURRENT_TIMESTAMP,
+    unique (denuncia_id, sha256)
+);
@@ -0,0 +1,6 @@
+-- usuario dono do cadastro de voluntario: so ele responde pelas proprias atribuicoes
+ALTER TABLE voluntarios
+ADD COLUMN user_id VARCHAR references users(id) ON DELETE SET NULL;
+
+-- um cadastro de voluntario por usuario em cada app
+CREATE UNIQUE INDEX uq_voluntarios_app_user ON voluntarios (app_id, user_id) WHERE user_id IS NOT NULL;
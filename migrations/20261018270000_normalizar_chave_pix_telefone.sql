@@ -0,0 +1,34 @@
+-- telefones gravados antes da validacao ((32) 98888-0000) e em E.164 (+5532988880000)
+-- passam a ter a mesma forma de comparacao: DDD + numero, sem o 55 e sem o 0 de discagem.
+-- cpf (11 digitos) e cnpj (14) nao tem o tamanho de telefone com 55 e ficam como estao
+CREATE OR REPLACE FUNCTION normalizar_chave_pix(chave text) RETURNS text
+LANGUAGE sql IMMUTABLE AS $$
+    SELECT CASE
+        WHEN trim(chave) ~ '^[0-9 .()/+-]+$' THEN
+            CASE
+                WHEN d ~ '^55[0-9]{10,11}$' THEN substr(d, 3)
+                WHEN d ~ '^0[0-9]{11}$' THEN substr(d, 2)
+                ELSE d
+            END
+        ELSE lower(trim(chave))
+    END
+    FROM (SELECT regexp_replace(chave, '\D', '', 'g') AS d) AS digitos
+$$;
+
+-- indices de expressao guardam o resultado antigo da funcao
+REINDEX INDEX idx_denuncias_chave_pix_confirmada;
+REINDEX INDEX idx_pontos_pix_normalizado;
+REINDEX INDEX idx_pontos_cnpj_normalizado;
+
+-- chaves antigas que agora casam com denuncias confirmadas (mesma regra de denuncias.rs)
+WITH confirmadas AS (
+    SELECT normalizar_chave_pix(chave_pix) AS chave, array_agg(DISTINCT app_id) AS apps, min(uid) AS denuncia
+    FROM denuncias
+    WHERE status = 'CONFIRMADA' AND normalizar_chave_pix(chave_pix) <> ''
+    GROUP BY 1
+)
+UPDATE pontos SET bloqueado_em = now(), denuncia_bloqueio = confirmadas.denuncia
+FROM confirmadas
+WHERE pontos.bloqueado_em IS NULL
+  AND confirmadas.chave IN (normalizar_chave_pix(pontos.pix), normalizar_chave_pix(pontos.cnpj))
+  AND (pontos.app_id = ANY(confirmadas.apps) OR cardinality(confirmadas.apps) >= 2);
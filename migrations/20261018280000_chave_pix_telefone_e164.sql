@@ -0,0 +1,86 @@
+-- volta a comparacao que separa os tipos de chave: telefone em E.164 tem 12 ou 13
+-- digitos (55 + DDD + numero), cpf 11 e cnpj 14. Tirar o 55 fazia um telefone
+-- casar com o cpf de mesmos digitos
+CREATE OR REPLACE FUNCTION normalizar_chave_pix(chave text) RETURNS text
+LANGUAGE sql IMMUTABLE AS $$
+    SELECT CASE
+        WHEN trim(chave) ~ '^[0-9 .()/+-]+$' THEN regexp_replace(chave, '\D', '', 'g')
+        ELSE lower(trim(chave))
+    END
+$$;
+
+REINDEX INDEX idx_denuncias_chave_pix_confirmada;
+REINDEX INDEX idx_pontos_pix_normalizado;
+REINDEX INDEX idx_pontos_cnpj_normalizado;
+
+-- telefones gravados antes da validacao ((32) 98888-0000) passam para +55...,
+-- com a mesma regra de validacao::normalizar_chave_pix: 11 digitos que fecham
+-- um cpf, ou pontuados como cpf, continuam sendo cpf
+CREATE OR REPLACE FUNCTION pg_temp.cpf_valido(d text) RETURNS boolean
+LANGUAGE plpgsql IMMUTABLE AS $$
+DECLARE
+    soma INTEGER;
+    dv INTEGER;
+BEGIN
+    IF d IS NULL OR d !~ '^[0-9]{11}$' OR d ~ '^(.)\1*$' THEN
+        RETURN false;
+    END IF;
+    FOR pos IN 10..11 LOOP
+        soma := 0;
+        FOR i IN 1..pos - 1 LOOP
+            soma := soma + substr(d, i, 1)::int * (pos + 1 - i);
+        END LOOP;
+        dv := CASE WHEN soma % 11 < 2 THEN 0 ELSE 11 - soma % 11 END;
+        IF dv <> substr(d, pos, 1)::int THEN
+            RETURN false;
+        END IF;
+    END LOOP;
+    RETURN true;
+END $$;
+
+CREATE OR REPLACE FUNCTION pg_temp.telefone_pix(chave text) RETURNS text
+LANGUAGE plpgsql IMMUTABLE AS $$
+DECLARE
+    d text := regexp_replace(chave, '\D', '', 'g');
+BEGIN
+    IF chave IS NULL OR trim(chave) !~ '^\+?[0-9 .()/-]+$' THEN
+        RETURN NULL;
+    END IF;
+    IF trim(chave) !~ '^\+' AND (length(d) NOT BETWEEN 10 AND 13
+        OR (length(d) = 11 AND (pg_temp.cpf_valido(d) OR chave LIKE '%.%'))) THEN
+        RETURN NULL;
+    END IF;
+    IF length(d) >= 12 AND d LIKE '55%' THEN
+        d := substr(d, 3);
+    END IF;
+    d := ltrim(d, '0');
+    IF length(d) NOT IN (10, 11) OR substr(d, 1, 2) LIKE '%0%' OR (length(d) = 11 AND substr(d, 3, 1) <> '9') THEN
+        RETURN NULL;
+    END IF;
+    RETURN '+55' || d;
+END $$;
+
+UPDATE pontos SET pix = pg_temp.telefone_pix(pix)
+WHERE pg_temp.telefone_pix(pix) <> pix;
+
+UPDATE denuncias SET chave_pix = pg_temp.telefone_pix(chave_pix)
+WHERE pg_temp.telefone_pix(chave_pix) <> chave_pix;
+
+-- refaz a quarentena com a comparacao corrigida. Libera quem nao casa mais com
+-- denuncia confirmada e bloqueia pela regra padrao (BLOQUEIO_MIN_APPS=1); com um
+-- minimo maior, a proxima moderacao de denuncia reavalia os pontos
+UPDATE pontos SET bloqueado_em = NULL, denuncia_bloqueio = NULL
+WHERE bloqueado_em IS NOT NULL
+  AND NOT EXISTS (
+      SELECT 1 FROM denuncias
+      WHERE status = 'CONFIRMADA'
+        AND normalizar_chave_pix(chave_pix) <> ''
+        AND normalizar_chave_pix(chave_pix) IN (normalizar_chave_pix(pontos.pix), normalizar_chave_pix(pontos.cnpj))
+  );
+
+UPDATE pontos SET bloqueado_em = now(), denuncia_bloqueio = denuncias.uid
+FROM denuncias
+WHERE pontos.bloqueado_em IS NULL
+  AND denuncias.status = 'CONFIRMADA'
+  AND normalizar_chave_pix(denuncias.chave_pix) <> ''
+  AND normalizar_chave_pix(denuncias.chave_pix) IN (normalizar_chave_pix(pontos.pix), normalizar_chave_pix(pontos.cnpj));
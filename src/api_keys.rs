@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Extension;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgExecutor;
+use utoipa::ToSchema;
+
+use crate::error::AppError;
+use crate::extract::{Json, Path};
+use crate::handlers::{ensure_papel, AppState};
+use crate::middleware::CurrentUser;
+use crate::models::{ApiKey, ApiKeyCriada};
+use crate::password::{hash_password, random_secret, verify_password};
+use crate::payloads::NewApiKey;
+use crate::roles::Papel;
+use crate::validacao::Validar;
+
+// Chaves de API dos aplicativos (gerenciadas por admins do app)
+//
+// Formato: <pk|sk>_<uid>.<segredo>
+// pk (publishable) e somente leitura e pode ir em frontends publicos;
+// sk (secret) permite escrita e deve ficar apenas no backend do app.
+// O banco guarda apenas o hash (argon2) do segredo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TipoChave {
+    Publishable,
+    Secret,
+}
+
+impl TipoChave {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TipoChave::Publishable => "publishable",
+            TipoChave::Secret => "secret",
+        }
+    }
+
+    fn prefixo(&self) -> &'static str {
+        match self {
+            TipoChave::Publishable => "pk",
+            TipoChave::Secret => "sk",
+        }
+    }
+}
+
+// Segredos ja verificados, por uid da chave, para nao rodar argon2 a cada requisicao.
+// Guarda apenas o sha256 do segredo, nunca o valor em claro.
+// A revogacao continua sendo consultada no banco em toda chamada.
+// uid -> (hash argon2 no banco, sha256 do segredo)
+type Verificadas = HashMap<String, (String, [u8; 32])>;
+
+#[derive(Clone, Default)]
+pub struct ApiKeyCache(Arc<Mutex<Verificadas>>);
+
+impl ApiKeyCache {
+    fn verificada(&self, uid: &str, key_hash: &str, segredo: &str) -> bool {
+        let digest = digest(segredo);
+        let cache = self.0.lock().expect("api key cache poisoned");
+        matches!(cache.get(uid), Some((hash, d)) if hash == key_hash && constant_time_eq(d, &digest))
+    }
+
+    fn guardar(&self, uid: &str, key_hash: &str, segredo: &str) {
+        let mut cache = self.0.lock().expect("api key cache poisoned");
+        cache.insert(uid.to_string(), (key_hash.to_string(), digest(segredo)));
+    }
+
+    fn remover(&self, uid: &str) {
+        let mut cache = self.0.lock().expect("api key cache poisoned");
+        cache.remove(uid);
+    }
+}
+
+fn digest(segredo: &str) -> [u8; 32] {
+    Sha256::digest(segredo.as_bytes()).into()
+}
+
+fn constant_time_eq(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+pub struct ChaveAutenticada {
+    pub app_id: String,
+    pub tipo: TipoChave,
+}
+
+// Valida a chave recebida no header x-emergencial-key
+pub async fn authenticate(state: &AppState, chave: &str) -> Result<Option<ChaveAutenticada>, sqlx::Error> {
+    let Some((prefixo, resto)) = chave.split_once('_') else { return Ok(None) };
+    let Some((uid, segredo)) = resto.split_once('.') else { return Ok(None) };
+
+    let row: Option<(String, String, String)> =
+        sqlx::query_as("SELECT app_id, app_keys.tipo, key_hash FROM app_keys JOIN app ON app.uid = app_id WHERE app_keys.uid = $1 AND revoked_at IS NULL AND app.ativo")
+            .bind(uid)
+            .fetch_optional(&state.db)
+            .await?;
+
+    let Some((app_id, tipo, key_hash)) = row else { return Ok(None) };
+
+    let tipo = match tipo.as_str() {
+        "secret" => TipoChave::Secret,
+        _ => TipoChave::Publishable,
+    };
+    if tipo.prefixo() != prefixo {
+        return Ok(None);
+    }
+
+    if !state.api_keys.verificada(uid, &key_hash, segredo) {
+        if !verify_password(segredo, &key_hash) {
+            return Ok(None);
+        }
+        state.api_keys.guardar(uid, &key_hash, segredo);
+    }
+
+    // registra o uso no maximo uma vez por minuto
+    sqlx::query("UPDATE app_keys SET last_used_at = now() WHERE uid = $1 AND (last_used_at IS NULL OR last_used_at < now() - interval '1 minute')")
+        .bind(uid)
+        .execute(&state.db)
+        .await?;
+
+    Ok(Some(ChaveAutenticada { app_id, tipo }))
+}
+
+// Gera uma nova chave; o valor completo so e devolvido aqui
+pub async fn create_key<'e>(
+    db: impl PgExecutor<'e>,
+    app_id: &str,
+    label: &str,
+    tipo: TipoChave,
+) -> Result<ApiKeyCriada, AppError> {
+    let segredo = random_secret();
+    let key_hash = hash_password(&segredo).map_err(|_| AppError::Internal)?;
+
+    let key =
+        sqlx::query_as::<_, ApiKey>(
+            r#"
+            INSERT INTO app_keys (app_id, label, tipo, key_hash)
+            VALUES ($1, $2, $3, $4)
+            RETURNING uid, app_id, label, tipo, created_at, last_used_at, revoked_at
+            "#
+        )
+        .bind(app_id)
+        .bind(label)
+        .bind(tipo.as_str())
+        .bind(&key_hash)
+        .fetch_one(db)
+        .await?;
+
+    let chave = format!("{}_{}.{}", tipo.prefixo(), key.uid, segredo);
+
+    Ok(ApiKeyCriada { key, chave })
+}
+
+#[utoipa::path(
+    get, path = "/apps/keys", tag = "apps",
+    responses((status = 200, body = Vec<ApiKey>))
+)]
+pub async fn list_keys(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+) -> Result<Json<Vec<ApiKey>>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Admin)?;
+
+    let rows =
+        sqlx::query_as::<_, ApiKey>(
+            r#"
+            select uid, app_id, label, tipo, created_at, last_used_at, revoked_at
+            from app_keys
+            where app_id = $1
+            order by created_at desc
+            "#
+        )
+        .bind(app_id)
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(rows))
+}
+
+#[utoipa::path(
+    post, path = "/apps/keys", tag = "apps",
+    request_body = NewApiKey,
+    responses((status = 200, body = ApiKeyCriada))
+)]
+pub async fn create_key_handler(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Json(payload): Json<NewApiKey>,
+) -> Result<Json<ApiKeyCriada>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Admin)?;
+    payload.validado()?;
+
+    let criada = create_key(&state.db, app_id, &payload.label, payload.tipo).await?;
+
+    Ok(Json(criada))
+}
+
+// Gera uma chave nova com o mesmo label e tipo e revoga a antiga
+#[utoipa::path(
+    post, path = "/apps/keys/{uid}/rotate", tag = "apps",
+    params(("uid" = String, Path)),
+    responses((status = 200, body = ApiKeyCriada))
+)]
+pub async fn rotate_key(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<Json<ApiKeyCriada>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Admin)?;
+
+    // revogar a antiga e criar a nova juntas: se a criacao falhar, a antiga continua valendo
+    let mut tx = state.db.begin().await?;
+
+    let antiga =
+        sqlx::query_as::<_, ApiKey>(
+            r#"
+            UPDATE app_keys SET revoked_at = now()
+            WHERE uid = $1 AND app_id = $2 AND revoked_at IS NULL
+            RETURNING uid, app_id, label, tipo, created_at, last_used_at, revoked_at
+            "#
+        )
+        .bind(&uid)
+        .bind(app_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let tipo = match antiga.tipo.as_str() {
+        "secret" => TipoChave::Secret,
+        _ => TipoChave::Publishable,
+    };
+
+    let criada = create_key(&mut *tx, app_id, &antiga.label, tipo).await?;
+
+    tx.commit().await?;
+    state.api_keys.remover(&uid);
+
+    Ok(Json(criada))
+}
+
+#[utoipa::path(
+    delete, path = "/apps/keys/{uid}", tag = "apps",
+    params(("uid" = String, Path)),
+    responses((status = 204))
+)]
+pub async fn revoke_key(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<StatusCode, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Admin)?;
+
+    let res = sqlx::query("UPDATE app_keys SET revoked_at = now() WHERE uid = $1 AND app_id = $2 AND revoked_at IS NULL")
+        .bind(&uid)
+        .bind(app_id)
+        .execute(&state.db)
+        .await?;
+
+    if res.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    state.api_keys.remover(&uid);
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,104 @@
+use std::env;
+use std::path::PathBuf;
+#[cfg(feature = "s3")]
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use thiserror::Error;
+
+// Onde ficam os arquivos de evidencia das denuncias
+//
+// EVIDENCIAS_STORAGE=local (padrao) grava em EVIDENCIAS_DIR (./evidencias);
+// EVIDENCIAS_STORAGE=s3 exige compilar com --features s3 e usa o bucket
+// EVIDENCIAS_S3_BUCKET com as credenciais AWS_* do ambiente (AWS_ENDPOINT
+// para servicos compativeis, como MinIO).
+pub enum Armazenamento {
+    Local(PathBuf),
+    #[cfg(feature = "s3")]
+    S3(Arc<dyn object_store::ObjectStore>),
+}
+
+#[derive(Debug, Error)]
+pub enum ArmazenamentoError {
+    #[error("unsupported EVIDENCIAS_STORAGE: {0} (use local or s3)")]
+    Unsupported(String),
+
+    #[error("EVIDENCIAS_STORAGE=s3 requires building with --features s3")]
+    S3Disabled,
+
+    #[error("EVIDENCIAS_S3_BUCKET must be set for EVIDENCIAS_STORAGE=s3")]
+    MissingBucket,
+
+    #[error("storage io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[cfg(feature = "s3")]
+    #[error("object store error: {0}")]
+    ObjectStore(#[from] object_store::Error),
+}
+
+impl Armazenamento {
+    pub fn from_env() -> Result<Self, ArmazenamentoError> {
+        let tipo = env::var("EVIDENCIAS_STORAGE").unwrap_or_else(|_| "local".into());
+        match tipo.as_str() {
+            "local" => {
+                let dir = env::var("EVIDENCIAS_DIR").unwrap_or_else(|_| "evidencias".into());
+                Ok(Armazenamento::local(dir)?)
+            }
+            "s3" => Self::s3(),
+            _ => Err(ArmazenamentoError::Unsupported(tipo)),
+        }
+    }
+
+    pub fn local(dir: impl Into<PathBuf>) -> Result<Self, ArmazenamentoError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Armazenamento::Local(dir))
+    }
+
+    #[cfg(feature = "s3")]
+    fn s3() -> Result<Self, ArmazenamentoError> {
+        let bucket = env::var("EVIDENCIAS_S3_BUCKET").map_err(|_| ArmazenamentoError::MissingBucket)?;
+        let store = object_store::aws::AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()?;
+        Ok(Armazenamento::S3(Arc::new(store)))
+    }
+
+    #[cfg(not(feature = "s3"))]
+    fn s3() -> Result<Self, ArmazenamentoError> {
+        if env::var("EVIDENCIAS_S3_BUCKET").is_err() {
+            return Err(ArmazenamentoError::MissingBucket);
+        }
+        Err(ArmazenamentoError::S3Disabled)
+    }
+
+    // a chave e montada pelo servidor (app/denuncia/sha256), nunca pelo cliente
+    pub async fn salvar(&self, chave: &str, dados: Bytes) -> Result<(), ArmazenamentoError> {
+        match self {
+            Armazenamento::Local(dir) => {
+                let caminho = dir.join(chave);
+                if let Some(pasta) = caminho.parent() {
+                    tokio::fs::create_dir_all(pasta).await?;
+                }
+                tokio::fs::write(caminho, &dados).await?;
+            }
+            #[cfg(feature = "s3")]
+            Armazenamento::S3(store) => {
+                store.put(&object_store::path::Path::from(chave), dados.into()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn ler(&self, chave: &str) -> Result<Bytes, ArmazenamentoError> {
+        match self {
+            Armazenamento::Local(dir) => Ok(Bytes::from(tokio::fs::read(dir.join(chave)).await?)),
+            #[cfg(feature = "s3")]
+            Armazenamento::S3(store) => {
+                let objeto = store.get(&object_store::path::Path::from(chave)).await?;
+                Ok(objeto.bytes().await?)
+            }
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use axum::extract::State;
+use axum::Extension;
+
+use sqlx::PgConnection;
+
+use crate::error::AppError;
+use crate::extract::{Json, Path};
+use crate::handlers::{alterar_status_solicitacao, ensure_papel, AppState};
+use crate::middleware::{CurrentUser, UserKind};
+use crate::models::Atribuicao;
+use crate::payloads::{NewAtribuicao, UpdateStatusAtribuicao};
+use crate::roles::Papel;
+use crate::validacao::Validar;
+use crate::workflow::{StatusAtribuicao, StatusSolicitacao};
+
+// Atribuicoes: voluntario designado por um coordenador para atender uma solicitacao
+// ou para um turno em um ponto; o voluntario aceita ou recusa e depois registra
+// o inicio e a conclusao
+const ATRIBUICAO_COLUNAS: &str =
+    "uid, app_id, voluntario_id, solicitacao_id, ponto_id, turno_inicio, turno_fim, status, observacao, \
+     aceita_em, recusada_em, iniciada_em, concluida_em, cancelada_em, created_by, created_at";
+
+#[utoipa::path(
+    post, path = "/solicitacoes/{uid}/atribuicoes", tag = "solicitacoes",
+    params(("uid" = String, Path)),
+    request_body = NewAtribuicao,
+    responses((status = 200, body = Atribuicao))
+)]
+pub async fn create_atribuicao_solicitacao(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+    Json(payload): Json<NewAtribuicao>,
+) -> Result<Json<Atribuicao>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Coordenador)?;
+    payload.validado()?;
+
+    // pedido e voluntario precisam ser do mesmo app de quem atribui
+    let sql = format!(
+        r#"
+        INSERT INTO atribuicoes (app_id, voluntario_id, solicitacao_id, observacao, created_by)
+        SELECT $1, voluntarios.uid, solicitacoes.uid, $4, $5
+        FROM voluntarios, solicitacoes
+        WHERE voluntarios.uid = $2 AND voluntarios.app_id = $1
+          AND solicitacoes.uid = $3 AND solicitacoes.app_id = $1
+        RETURNING {ATRIBUICAO_COLUNAS}
+        "#
+    );
+
+    let record =
+        sqlx::query_as::<_, Atribuicao>(&sql)
+            .bind(app_id)
+            .bind(&payload.voluntario_uid)
+            .bind(&uid)
+            .bind(&payload.observacao)
+            .bind(&current.user_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+    Ok(Json(record))
+}
+
+#[utoipa::path(
+    get, path = "/solicitacoes/{uid}/atribuicoes", tag = "solicitacoes",
+    params(("uid" = String, Path)),
+    responses((status = 200, body = Vec<Atribuicao>))
+)]
+pub async fn list_atribuicoes_solicitacao(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<Json<Vec<Atribuicao>>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
+
+    let sql = format!(
+        "SELECT {ATRIBUICAO_COLUNAS} FROM atribuicoes WHERE solicitacao_id = $1 AND app_id = $2 ORDER BY created_at DESC"
+    );
+
+    let rows =
+        sqlx::query_as::<_, Atribuicao>(&sql)
+            .bind(&uid)
+            .bind(app_id)
+            .fetch_all(&state.db)
+            .await?;
+
+    Ok(Json(rows))
+}
+
+#[utoipa::path(
+    post, path = "/pontos/{uid}/atribuicoes", tag = "pontos",
+    params(("uid" = String, Path)),
+    request_body = NewAtribuicao,
+    responses((status = 200, body = Atribuicao))
+)]
+pub async fn create_atribuicao_ponto(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+    Json(payload): Json<NewAtribuicao>,
+) -> Result<Json<Atribuicao>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Coordenador)?;
+    payload.validado()?;
+
+    let (Some(inicio), Some(fim)) = (payload.turno_inicio, payload.turno_fim) else {
+        return Err(AppError::Validation("turno_inicio e turno_fim sao obrigatorios".into()));
+    };
+    if fim <= inicio {
+        return Err(AppError::Validation("turno_fim deve ser depois de turno_inicio".into()));
+    }
+
+    let sql = format!(
+        r#"
+        INSERT INTO atribuicoes (app_id, voluntario_id, ponto_id, turno_inicio, turno_fim, observacao, created_by)
+        SELECT $1, voluntarios.uid, pontos.uid, $4, $5, $6, $7
+        FROM voluntarios, pontos
+        WHERE voluntarios.uid = $2 AND voluntarios.app_id = $1
+          AND pontos.uid = $3 AND pontos.app_id = $1
+        RETURNING {ATRIBUICAO_COLUNAS}
+        "#
+    );
+
+    let record =
+        sqlx::query_as::<_, Atribuicao>(&sql)
+            .bind(app_id)
+            .bind(&payload.voluntario_uid)
+            .bind(&uid)
+            .bind(inicio)
+            .bind(fim)
+            .bind(&payload.observacao)
+            .bind(&current.user_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+    Ok(Json(record))
+}
+
+#[utoipa::path(
+    get, path = "/pontos/{uid}/atribuicoes", tag = "pontos",
+    params(("uid" = String, Path)),
+    responses((status = 200, body = Vec<Atribuicao>))
+)]
+pub async fn list_atribuicoes_ponto(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<Json<Vec<Atribuicao>>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
+
+    let sql = format!(
+        "SELECT {ATRIBUICAO_COLUNAS} FROM atribuicoes WHERE ponto_id = $1 AND app_id = $2 ORDER BY turno_inicio"
+    );
+
+    let rows =
+        sqlx::query_as::<_, Atribuicao>(&sql)
+            .bind(&uid)
+            .bind(app_id)
+            .fetch_all(&state.db)
+            .await?;
+
+    Ok(Json(rows))
+}
+
+// Transicao de status da atribuicao; cancelar cabe ao coordenador,
+// aceitar, recusar, iniciar e concluir ao usuario do voluntario atribuido
+#[utoipa::path(
+    post, path = "/atribuicoes/{uid}/status", tag = "atribuicoes",
+    params(("uid" = String, Path)),
+    request_body = UpdateStatusAtribuicao,
+    responses((status = 200, body = Atribuicao))
+)]
+pub async fn update_status_atribuicao(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+    Json(payload): Json<UpdateStatusAtribuicao>,
+) -> Result<Json<Atribuicao>, AppError> {
+
+    let papel = match payload.status {
+        StatusAtribuicao::Cancelada => Papel::Coordenador,
+        _ => Papel::Voluntario,
+    };
+    let app_id = ensure_papel(&current, papel)?;
+    payload.validado()?;
+
+    let mut tx = state.db.begin().await?;
+
+    let (atual, responsavel): (String, Option<String>) =
+        sqlx::query_as(
+            r#"
+            SELECT atribuicoes.status, voluntarios.user_id
+            FROM atribuicoes JOIN voluntarios ON voluntarios.uid = atribuicoes.voluntario_id
+            WHERE atribuicoes.uid = $1 AND atribuicoes.app_id = $2
+            FOR UPDATE OF atribuicoes
+            "#
+        )
+            .bind(&uid)
+            .bind(app_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+    // so o usuario ligado ao voluntario responde pela atribuicao; chaves de app nao
+    if papel == Papel::Voluntario
+        && (current.kind != UserKind::User || responsavel.as_deref() != Some(current.user_id.as_str()))
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let atual: StatusAtribuicao = atual
+        .parse()
+        .map_err(AppError::Conflict)?;
+
+    if !atual.pode_ir_para(payload.status) {
+        return Err(AppError::Conflict(format!(
+            "transicao invalida: {} -> {}",
+            atual, payload.status
+        )));
+    }
+
+    // a coluna vem de um conjunto fixo (coluna_horario), nunca do payload
+    let horario = payload.status.coluna_horario().unwrap_or("updated_at");
+    let sql = format!(
+        r#"
+        UPDATE atribuicoes
+        SET status = $1, observacao = COALESCE($2, observacao), {horario} = now(), updated_at = now()
+        WHERE uid = $3
+        RETURNING {ATRIBUICAO_COLUNAS}
+        "#
+    );
+
+    let record =
+        sqlx::query_as::<_, Atribuicao>(&sql)
+            .bind(payload.status.as_str())
+            .bind(&payload.observacao)
+            .bind(&uid)
+            .fetch_one(&mut *tx)
+            .await?;
+
+    if let Some(solicitacao_id) = record.solicitacao_id.as_deref() {
+        refletir_na_solicitacao(&mut tx, &current, app_id, solicitacao_id, payload.status, payload.atender_solicitacao).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(Json(record))
+}
+
+// Mantem o status da solicitacao coerente com suas atribuicoes:
+// aceite coloca o pedido em atendimento, a conclusao pode encerra-lo e,
+// se nao sobrar ninguem atribuido, o pedido volta para a fila.
+// Pedido que ja nao aceita a transicao (atendido por outro voluntario,
+// cancelado, duplicado) fica como esta e a atribuicao segue normalmente
+async fn refletir_na_solicitacao(
+    conn: &mut PgConnection,
+    current: &CurrentUser,
+    app_id: &str,
+    solicitacao_id: &str,
+    novo: StatusAtribuicao,
+    atender: bool,
+) -> Result<(), AppError> {
+
+    let status: String =
+        sqlx::query_scalar("SELECT status FROM solicitacoes WHERE uid = $1 AND app_id = $2 FOR UPDATE")
+            .bind(solicitacao_id)
+            .bind(app_id)
+            .fetch_one(&mut *conn)
+            .await?;
+
+    let status: StatusSolicitacao = status
+        .parse()
+        .map_err(AppError::Conflict)?;
+
+    let destino = match novo {
+        StatusAtribuicao::Aceita if status == StatusSolicitacao::Novo => {
+            Some((StatusSolicitacao::EmAtendimento, "voluntario aceitou a atribuicao"))
+        }
+        StatusAtribuicao::Concluida if atender && status.pode_ir_para(StatusSolicitacao::Atendido) => {
+            Some((StatusSolicitacao::Atendido, "atribuicao concluida"))
+        }
+        StatusAtribuicao::Recusada | StatusAtribuicao::Cancelada
+            if status == StatusSolicitacao::EmAtendimento =>
+        {
+            let restantes: i64 =
+                sqlx::query_scalar("SELECT COUNT(*) FROM atribuicoes WHERE solicitacao_id = $1 AND status IN ('ACEITA', 'EM_ANDAMENTO')")
+                    .bind(solicitacao_id)
+                    .fetch_one(&mut *conn)
+                    .await?;
+            (restantes == 0).then_some((StatusSolicitacao::Novo, "atribuicao encerrada sem atendimento"))
+        }
+        _ => None,
+    };
+
+    if let Some((destino, observacao)) = destino {
+        alterar_status_solicitacao(conn, current, app_id, solicitacao_id, destino, Some(observacao)).await?;
+    }
+
+    Ok(())
+}
@@ -1,60 +1,293 @@
 use axum::{
     extract::State,
+    http::StatusCode,
     // routing::post,
-    Json,
+    Extension,
     // Router,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-// use uuid::Uuid;
+use sqlx::{FromRow, PgConnection};
+use tracing::warn;
+use uuid::Uuid;
+use utoipa::ToSchema;
 
+use crate::error::AppError;
+use crate::extract::Json;
 use crate::handlers::AppState;
 use crate::models::User;
-use crate::jwt;
+use crate::jwt::{self, Claims};
+use crate::roles::Papel;
 
 //modulo especifico para implementar hash de senha segura
-use crate::password::verify_password;
+use crate::password::{hash_password, random_secret, verify_password};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct AuthRequest {
     pub email: String,
     pub senha: String,
+    // app da sessao; se ausente, o app em que o usuario se cadastrou
+    pub app_id: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct AuthResponse {
     pub token: String,
+    pub refresh_token: String,
+    // validade do access token em segundos
+    pub expires_in: i64,
 }
 
- pub async fn generate_token(
+#[derive(Deserialize, ToSchema)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+#[derive(FromRow)]
+struct RefreshTokenRow {
+    user_id: String,
+    sessao_id: String,
+    app_id: Option<String>,
+    token_hash: String,
+    expires_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Default, ToSchema)]
+pub struct LogoutRequest {
+    // encerra tambem as sessoes de outros dispositivos (ex: celular perdido)
+    #[serde(default)]
+    pub todas_sessoes: bool,
+}
+
+#[utoipa::path(
+    method(post, get), path = "/login", tag = "auth",
+    request_body = AuthRequest,
+    responses((status = 200, body = AuthResponse)),
+    security(())
+)]
+pub async fn generate_token(
     State(state): State<AppState>,
     Json(payload): Json<AuthRequest>,
-) -> Result<Json<AuthResponse>, axum::http::StatusCode> {
+) -> Result<Json<AuthResponse>, AppError> {
     let user = sqlx::query_as!(
         User,
-        r#"SELECT id, nome, email, senha FROM users WHERE email = $1"#,
-        payload.email
+        r#"SELECT id, nome, email, senha FROM users WHERE email = $1 AND ($2::varchar IS NULL OR app_id = $2)"#,
+        payload.email,
+        payload.app_id
     )
     .fetch_optional(&state.db)
-    .await
-    .map_err(|_| axum::http::StatusCode::INTERNAL_SERVER_ERROR)?;
+    .await?;
 
     let user = match user {
         Some(u) => u,
         None => {
             const DUMMY_HASH: &str = "f1248550-daf0-5d4e-b509-417aa5b2172c943a7072-0e1a-5465-9f34-5d5482d23239";
             let _ = verify_password(&payload.senha, DUMMY_HASH);     
-            return Err(axum::http::StatusCode::UNAUTHORIZED)
+            return Err(AppError::Unauthorized)
         },    
     };
 
     // 🔐 verificação segura
     let password_valid = verify_password(&payload.senha, &user.senha);
     if !password_valid {
-        return Err(axum::http::StatusCode::UNAUTHORIZED);
+        return Err(AppError::Unauthorized);
+    }
+
+    let mut conn = state.db.acquire().await?;
+
+    let app_id: Option<String> = match payload.app_id {
+        Some(app_id) => Some(app_id),
+        None => sqlx::query_scalar("SELECT app_id FROM users WHERE id = $1")
+            .bind(&user.id)
+            .fetch_one(&mut *conn)
+            .await?,
+    };
+
+    // cada login abre uma nova sessao
+    let sessao_id = Uuid::new_v4().to_string();
+    let response = issue_tokens(&state, &mut conn, &user.id, &sessao_id, app_id.as_deref()).await?;
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post, path = "/token/refresh", tag = "auth",
+    request_body = RefreshRequest,
+    responses((status = 200, body = AuthResponse)),
+    security(())
+)]
+pub async fn refresh_token(
+    State(state): State<AppState>,
+    Json(payload): Json<RefreshRequest>,
+) -> Result<Json<AuthResponse>, AppError> {
+    // formato: <uid>.<segredo>
+    let (uid, segredo) = payload
+        .refresh_token
+        .split_once('.')
+        .ok_or(AppError::Unauthorized)?;
+
+    let mut tx = state.db.begin().await?;
+
+    // FOR UPDATE: duas renovacoes simultaneas do mesmo token nao podem ambas vencer
+    let row =
+        sqlx::query_as::<_, RefreshTokenRow>(
+            "SELECT user_id, sessao_id, app_id, token_hash, expires_at, revoked_at FROM refresh_tokens WHERE uid = $1 FOR UPDATE"
+        )
+        .bind(uid)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+    let RefreshTokenRow { user_id, sessao_id, app_id, token_hash, expires_at, revoked_at } =
+        row.ok_or(AppError::Unauthorized)?;
+
+    if !verify_password(segredo, &token_hash) {
+        return Err(AppError::Unauthorized);
+    }
+
+    if revoked_at.is_some() {
+        // token ja usado: provavel vazamento, derruba a sessao inteira
+        warn!("refresh token reuse detected, revoking session {sessao_id}");
+        revoke_tokens(&state, &mut tx, &user_id, Some(&sessao_id)).await?;
+        tx.commit().await?;
+        return Err(AppError::Unauthorized);
     }
 
-    let token = jwt::generate(&user.id) 
-        .map_err(|_| axum::http::StatusCode::INTERNAL_SERVER_ERROR)?;
+    if expires_at < Utc::now() {
+        return Err(AppError::Unauthorized);
+    }
+
+    sqlx::query("UPDATE refresh_tokens SET revoked_at = now() WHERE uid = $1")
+        .bind(uid)
+        .execute(&mut *tx)
+        .await?;
+
+    let response = issue_tokens(&state, &mut tx, &user_id, &sessao_id, app_id.as_deref()).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post, path = "/logout", tag = "auth",
+    request_body = Option<LogoutRequest>,
+    responses((status = 204))
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    claims: Option<Extension<Claims>>,
+    payload: Option<Json<LogoutRequest>>,
+) -> Result<StatusCode, AppError> {
+    // somente sessoes de usuario (Bearer); apps nao tem o que encerrar
+    let Extension(claims) = claims
+        .ok_or_else(|| AppError::BadRequest("logout disponivel apenas para sessoes de usuario".into()))?;
+    let Json(payload) = payload.unwrap_or_default();
+
+    let mut tx = state.db.begin().await?;
+
+    let sessao = if payload.todas_sessoes { None } else { Some(claims.sid.as_str()) };
+    revoke_tokens(&state, &mut tx, &claims.sub, sessao).await?;
+
+    let exp = DateTime::<Utc>::from_timestamp(claims.exp as i64, 0)
+        .unwrap_or_else(Utc::now);
+
+    sqlx::query("INSERT INTO tokens_revogados (jti, user_id, expires_at) VALUES ($1, $2, $3) ON CONFLICT (jti) DO NOTHING")
+        .bind(&claims.jti)
+        .bind(&claims.sub)
+        .bind(exp)
+        .execute(&mut *tx)
+        .await?;
+
+    // limpeza: revogacoes de tokens ja expirados nao precisam ser guardadas
+    sqlx::query("DELETE FROM tokens_revogados WHERE expires_at < now()")
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Emite o par access token + refresh token para a sessao.
+// O refresh token guarda o jti do access token emitido junto para permitir revoga-lo.
+// O papel e relido a cada emissao, entao mudancas valem a partir da proxima renovacao.
+async fn issue_tokens(
+    state: &AppState,
+    conn: &mut PgConnection,
+    user_id: &str,
+    sessao_id: &str,
+    app_id: Option<&str>,
+) -> Result<AuthResponse, AppError> {
+    let papel: Option<String> =
+        sqlx::query_scalar("SELECT papel FROM papeis_usuarios WHERE user_id = $1 AND app_id = $2")
+            .bind(user_id)
+            .bind(app_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+    let papel: Option<Papel> = papel.and_then(|p| p.parse().ok());
+
+    let (token, claims) = jwt::generate(&state.jwt, user_id, sessao_id, app_id, papel)
+        .map_err(|_| AppError::Internal)?;
+
+    let segredo = random_secret();
+    let token_hash = hash_password(&segredo)?;
+
+    let uid: String = sqlx::query_scalar(
+        r#"
+        INSERT INTO refresh_tokens (user_id, sessao_id, app_id, token_hash, access_jti, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING uid
+        "#
+    )
+    .bind(user_id)
+    .bind(sessao_id)
+    .bind(app_id)
+    .bind(&token_hash)
+    .bind(&claims.jti)
+    .bind(Utc::now() + state.jwt.refresh_expiration)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(AuthResponse {
+        token,
+        refresh_token: format!("{uid}.{segredo}"),
+        expires_in: state.jwt.expiration.num_seconds(),
+    })
+}
+
+// Revoga os refresh tokens do usuario (de uma sessao ou de todas) e os access
+// tokens emitidos com eles que ainda podem estar dentro da validade
+async fn revoke_tokens(
+    state: &AppState,
+    conn: &mut PgConnection,
+    user_id: &str,
+    sessao_id: Option<&str>,
+) -> Result<(), AppError> {
+    let agora = Utc::now();
+
+    sqlx::query(
+        r#"
+        WITH revogados AS (
+            UPDATE refresh_tokens SET revoked_at = COALESCE(revoked_at, now())
+            WHERE user_id = $1
+              AND ($2::varchar IS NULL OR sessao_id = $2)
+              AND (revoked_at IS NULL OR created_at > $3)
+            RETURNING user_id, access_jti, created_at
+        )
+        INSERT INTO tokens_revogados (jti, user_id, expires_at)
+        SELECT access_jti, user_id, $4 FROM revogados
+        WHERE created_at > $3
+        ON CONFLICT (jti) DO NOTHING
+        "#
+    )
+    .bind(user_id)
+    .bind(sessao_id)
+    .bind(agora - state.jwt.expiration)
+    .bind(agora + state.jwt.expiration)
+    .execute(&mut *conn)
+    .await?;
 
-    Ok(Json(AuthResponse {token}))
+    Ok(())
 }
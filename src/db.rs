@@ -1,4 +1,3 @@
-use axum::http::StatusCode;
 use sqlx::{Pool, Postgres};  
   
 pub type DbPool = Pool<Postgres>;  
@@ -7,11 +6,4 @@ pub async fn connect(database_url: &str) -> DbPool {
 Pool::<Postgres>::connect(database_url)  
 .await  
 .expect("Failed to connect to Postgres")  
-}
-
-pub fn internal_error<E: std::fmt::Display>(err: E) -> (StatusCode, String) {
-    (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        format!("DB error: {err}"),
-    )
 }
\ No newline at end of file
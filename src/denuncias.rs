@@ -0,0 +1,325 @@
+use std::env;
+
+use axum::extract::State;
+use axum::Extension;
+use sqlx::{PgConnection, PgExecutor};
+
+use crate::error::AppError;
+use crate::extract::{Json, Path};
+use crate::handlers::{ensure_papel, AppState};
+use crate::middleware::CurrentUser;
+use crate::models::{Blocklist, Denuncia, ModeracaoDenuncia, PixStatus};
+use crate::payloads::UpdateStatusDenuncia;
+use crate::roles::Papel;
+use crate::validacao::{normalizar_chave_pix, Validar};
+use crate::workflow::StatusDenuncia;
+
+// Moderacao das denuncias e consultas publicas da lista de bloqueio
+//
+// Coordenadores do app que recebeu a denuncia fazem a triagem. Toda chave
+// confirmada entra na lista publica. A confirmacao sempre bloqueia a chave
+// no app que confirmou; nos demais apps vale a partir de BLOQUEIO_MIN_APPS
+// apps diferentes (padrao 1, qualquer confirmacao).
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigBloqueio {
+    pub min_apps: i64,
+}
+
+impl Default for ConfigBloqueio {
+    fn default() -> Self {
+        Self { min_apps: 1 }
+    }
+}
+
+impl ConfigBloqueio {
+    pub fn from_env() -> Result<Self, String> {
+        match env::var("BLOQUEIO_MIN_APPS") {
+            Ok(valor) => match valor.trim().parse() {
+                Ok(min_apps) if min_apps >= 1 => Ok(Self { min_apps }),
+                _ => Err(format!("BLOQUEIO_MIN_APPS must be a number >= 1, got {valor:?}")),
+            },
+            Err(_) => Ok(Self::default()),
+        }
+    }
+}
+
+// chaves pix confirmadas como golpe e os apps que confirmaram cada uma
+const CHAVES_CONFIRMADAS: &str = r#"
+    confirmadas AS (
+        SELECT normalizar_chave_pix(chave_pix) AS chave, array_agg(DISTINCT app_id) AS apps, min(uid) AS denuncia
+        FROM denuncias
+        WHERE status = 'CONFIRMADA' AND normalizar_chave_pix(chave_pix) <> ''
+        GROUP BY 1
+    )"#;
+
+const DENUNCIA_COLUNAS: &str =
+    "denuncias.uid, denuncias.tipo, denuncias.app_id, app.nome as origem, memorando, denuncias.url, denuncias.chave_pix, \
+     denuncias.evidencias, denuncias.metadados, denuncias.status, denuncias.moderado_em, denuncias.created_by, denuncias.created_at";
+
+#[utoipa::path(
+    post, path = "/denuncias/{uid}/status", tag = "denuncias",
+    params(("uid" = String, Path)),
+    request_body = UpdateStatusDenuncia,
+    responses((status = 200, body = Denuncia))
+)]
+pub async fn update_status_denuncia(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+    Json(payload): Json<UpdateStatusDenuncia>,
+) -> Result<Json<Denuncia>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Coordenador)?;
+    payload.validado()?;
+
+    let mut tx = state.db.begin().await?;
+
+    // trava a linha para evitar duas transicoes concorrentes
+    let atual: String =
+        sqlx::query_scalar("SELECT status FROM denuncias WHERE uid = $1 AND app_id = $2 FOR UPDATE")
+            .bind(&uid)
+            .bind(app_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+    let atual: StatusDenuncia = atual
+        .parse()
+        .map_err(AppError::Conflict)?;
+
+    if !atual.pode_ir_para(payload.status) {
+        return Err(AppError::Conflict(format!(
+            "transicao invalida: {} -> {}",
+            atual, payload.status
+        )));
+    }
+
+    sqlx::query("UPDATE denuncias SET status = $1, moderado_em = now() WHERE uid = $2")
+        .bind(payload.status.as_str())
+        .bind(&uid)
+        .execute(&mut *tx)
+        .await?;
+
+    // confirmar coloca em quarentena os pontos com a chave; descartar libera
+    if atual == StatusDenuncia::Confirmada || payload.status == StatusDenuncia::Confirmada {
+        reavaliar_bloqueios(&mut tx, state.bloqueio, None).await?;
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO denuncias_moderacao
+        (denuncia_id, app_id, status_anterior, status_novo,
+         nota, moderado_por, moderado_por_tipo)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#
+    )
+    .bind(&uid)
+    .bind(app_id)
+    .bind(atual.as_str())
+    .bind(payload.status.as_str())
+    .bind(&payload.nota)
+    .bind(&current.user_id)
+    .bind(current.kind.to_string())
+    .execute(&mut *tx)
+    .await?;
+
+    let sql = format!("SELECT {DENUNCIA_COLUNAS} FROM denuncias JOIN app ON app_id = app.uid WHERE denuncias.uid = $1");
+    let record =
+        sqlx::query_as::<_, Denuncia>(&sql)
+            .bind(&uid)
+            .fetch_one(&mut *tx)
+            .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(record))
+}
+
+// Notas e mudancas de status registradas pelos moderadores
+#[utoipa::path(
+    get, path = "/denuncias/{uid}/moderacao", tag = "denuncias",
+    params(("uid" = String, Path)),
+    responses((status = 200, body = Vec<ModeracaoDenuncia>))
+)]
+pub async fn list_moderacao_denuncia(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<Json<Vec<ModeracaoDenuncia>>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Coordenador)?;
+
+    let rows =
+        sqlx::query_as::<_, ModeracaoDenuncia>(
+            r#"
+            SELECT uid, denuncia_id, status_anterior, status_novo, nota,
+                   moderado_por, moderado_por_tipo, created_at
+            FROM denuncias_moderacao
+            WHERE denuncia_id = $1 AND app_id = $2
+            ORDER BY created_at
+            "#
+        )
+        .bind(&uid)
+        .bind(app_id)
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(rows))
+}
+
+// Rota publica: chaves pix e urls confirmadas como golpe
+#[utoipa::path(
+    get, path = "/denuncias/blocklist", tag = "denuncias",
+    responses((status = 200, body = Blocklist)),
+    security(())
+)]
+pub async fn blocklist(
+    State(state): State<AppState>,
+) -> Result<Json<Blocklist>, AppError> {
+
+    let chaves_pix: Vec<String> =
+        sqlx::query_scalar(
+            r#"
+            SELECT min(trim(chave_pix)) FROM denuncias
+            WHERE status = 'CONFIRMADA' AND coalesce(trim(chave_pix), '') <> ''
+            GROUP BY normalizar_chave_pix(chave_pix)
+            ORDER BY 1
+            "#
+        )
+        .fetch_all(&state.db)
+        .await?;
+
+    let urls: Vec<String> =
+        sqlx::query_scalar(
+            r#"
+            SELECT trim(url) FROM denuncias
+            WHERE status = 'CONFIRMADA' AND coalesce(trim(url), '') <> ''
+            GROUP BY trim(url)
+            ORDER BY 1
+            "#
+        )
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(Blocklist { chaves_pix, urls }))
+}
+
+// Rota publica: consulta de uma chave antes da doacao
+#[utoipa::path(
+    get, path = "/pix/{chave}/status", tag = "denuncias",
+    params(("chave" = String, Path)),
+    responses((status = 200, body = PixStatus)),
+    security(())
+)]
+pub async fn pix_status(
+    State(state): State<AppState>,
+    Path(chave): Path<String>,
+) -> Result<Json<PixStatus>, AppError> {
+
+    let (tipo, chave) = normalizar_chave_pix(&chave).map_err(|mensagem| AppError::campo("chave", mensagem))?;
+
+    let (confirmadas, apps, em_analise): (i64, i64, i64) =
+        sqlx::query_as(
+            r#"
+            SELECT count(*) FILTER (WHERE status = 'CONFIRMADA'),
+                   count(DISTINCT app_id) FILTER (WHERE status = 'CONFIRMADA'),
+                   count(*) FILTER (WHERE status = 'EM_ANALISE')
+            FROM denuncias
+            WHERE normalizar_chave_pix(chave_pix) = normalizar_chave_pix($1)
+            "#
+        )
+        .bind(&chave)
+        .fetch_one(&state.db)
+        .await?;
+
+    Ok(Json(PixStatus {
+        chave,
+        tipo,
+        bloqueada: confirmadas > 0,
+        denuncias_confirmadas: confirmadas,
+        apps_que_confirmaram: apps,
+        denuncias_em_analise: em_analise,
+    }))
+}
+
+// Recusa pix ou cnpj com denuncia de golpe confirmada pelo proprio app ou por min_apps apps
+pub async fn ensure_chaves_liberadas<'e>(
+    db: impl PgExecutor<'e>,
+    config: ConfigBloqueio,
+    app_id: &str,
+    chaves: &[Option<&str>],
+) -> Result<(), AppError> {
+
+    let chaves: Vec<&str> = chaves.iter().flatten().copied().filter(|c| !c.trim().is_empty()).collect();
+    if chaves.is_empty() {
+        return Ok(());
+    }
+
+    let sql = format!(
+        r#"
+        WITH {CHAVES_CONFIRMADAS}
+        SELECT informada FROM unnest($1::varchar[]) AS informada
+        JOIN confirmadas ON confirmadas.chave = normalizar_chave_pix(informada)
+        WHERE $2 = ANY(confirmadas.apps) OR cardinality(confirmadas.apps) >= $3
+        LIMIT 1
+        "#
+    );
+
+    let denunciada: Option<String> =
+        sqlx::query_scalar(&sql)
+            .bind(&chaves)
+            .bind(app_id)
+            .bind(config.min_apps)
+            .fetch_optional(db)
+            .await?;
+
+    match denunciada {
+        Some(chave) => Err(AppError::Conflict(format!("chave denunciada como golpe: {chave}"))),
+        None => Ok(()),
+    }
+}
+
+// Sincroniza a quarentena dos pontos com as denuncias confirmadas:
+// libera quem nao tem mais chave denunciada e bloqueia quem passou a ter.
+// Vale a confirmacao do app do ponto ou a de min_apps apps.
+// Sem ponto_uid reavalia todos os pontos.
+pub async fn reavaliar_bloqueios(conn: &mut PgConnection, config: ConfigBloqueio, ponto_uid: Option<&str>) -> Result<(), AppError> {
+
+    let liberar = format!(
+        r#"
+        WITH {CHAVES_CONFIRMADAS}
+        UPDATE pontos SET bloqueado_em = NULL, denuncia_bloqueio = NULL
+        WHERE bloqueado_em IS NOT NULL
+          AND ($1::varchar IS NULL OR uid = $1)
+          AND NOT EXISTS (
+              SELECT 1 FROM confirmadas
+              WHERE confirmadas.chave IN (normalizar_chave_pix(pontos.pix), normalizar_chave_pix(pontos.cnpj))
+                AND (pontos.app_id = ANY(confirmadas.apps) OR cardinality(confirmadas.apps) >= $2)
+          )
+        "#
+    );
+    sqlx::query(&liberar)
+        .bind(ponto_uid)
+        .bind(config.min_apps)
+        .execute(&mut *conn)
+        .await?;
+
+    let bloquear = format!(
+        r#"
+        WITH {CHAVES_CONFIRMADAS}
+        UPDATE pontos SET bloqueado_em = now(), denuncia_bloqueio = confirmadas.denuncia
+        FROM confirmadas
+        WHERE pontos.bloqueado_em IS NULL
+          AND ($1::varchar IS NULL OR pontos.uid = $1)
+          AND confirmadas.chave IN (normalizar_chave_pix(pontos.pix), normalizar_chave_pix(pontos.cnpj))
+          AND (pontos.app_id = ANY(confirmadas.apps) OR cardinality(confirmadas.apps) >= $2)
+        "#
+    );
+    sqlx::query(&bloquear)
+        .bind(ponto_uid)
+        .bind(config.min_apps)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
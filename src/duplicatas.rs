@@ -0,0 +1,265 @@
+use axum::extract::State;
+use axum::Extension;
+use sqlx::PgExecutor;
+
+use crate::error::AppError;
+use crate::extract::{Json, Path};
+use crate::handlers::{alterar_status_solicitacao, ensure_papel, fetch_solicitacao, AppState};
+use crate::middleware::CurrentUser;
+use crate::models::Solicitacao;
+use crate::payloads::MesclarSolicitacao;
+use crate::roles::Papel;
+use crate::validacao::Validar;
+use crate::workflow::StatusSolicitacao;
+
+// Deteccao de solicitacoes duplicadas
+//
+// A mesma familia costuma pedir ajuda por varios apps. Ao criar uma solicitacao
+// procuramos, em todos os apps, um pedido recente com o mesmo telefone, no mesmo
+// municipio e com nome parecido; se houver, a nova fica marcada com duplicada_de
+// apontando para o original ate um coordenador mesclar (ou ignorar) a marcacao.
+const JANELA_HORAS: i32 = 72;
+
+// nomes com similaridade a partir disso sao considerados a mesma pessoa
+const SIMILARIDADE_MINIMA: f64 = 0.8;
+
+fn normalizar_nome(nome: &str) -> Vec<String> {
+    nome.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'í' | 'ì' | 'î' | 'ï' => 'i',
+            'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+            'ú' | 'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        })
+        .collect::<String>()
+        .split_whitespace()
+        // preposicoes nao ajudam a diferenciar nomes
+        .filter(|p| !matches!(*p, "da" | "de" | "do" | "das" | "dos" | "e"))
+        .map(str::to_string)
+        .collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut linha: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut anterior = linha[0];
+        linha[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let atual = linha[j + 1];
+            linha[j + 1] = if ca == *cb {
+                anterior
+            } else {
+                1 + anterior.min(atual).min(linha[j])
+            };
+            anterior = atual;
+        }
+    }
+    linha[b.len()]
+}
+
+// "Maria Silva" e "maria da silva" ou "Maria Silva Souza" contam como parecidos:
+// o nome mais curto contido no mais longo ou poucas letras de diferenca
+pub fn nomes_parecidos(a: &str, b: &str) -> bool {
+    let (a, b) = (normalizar_nome(a), normalizar_nome(b));
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+
+    let (curto, longo) = if a.len() <= b.len() { (&a, &b) } else { (&b, &a) };
+    if curto.iter().all(|p| longo.contains(p)) {
+        return true;
+    }
+
+    let (a, b) = (a.join(" "), b.join(" "));
+    let maior = a.chars().count().max(b.chars().count());
+    1.0 - levenshtein(&a, &b) as f64 / maior as f64 >= SIMILARIDADE_MINIMA
+}
+
+// Pedido original mais antigo, em qualquer app, de que a nova solicitacao parece duplicata;
+// telefone_normalizado vem de validacao::telefone_nacional
+pub async fn buscar_original<'e>(
+    db: impl PgExecutor<'e>,
+    telefone_normalizado: &str,
+    municipio: &str,
+    nome: &str,
+) -> Result<Option<String>, AppError> {
+
+    if telefone_normalizado.is_empty() {
+        return Ok(None);
+    }
+
+    let candidatos: Vec<(String, String)> =
+        sqlx::query_as(
+            r#"
+            SELECT uid, nome FROM solicitacoes
+            WHERE telefone_normalizado = $1
+              AND lower(municipio) = lower($2)
+              AND created_at > now() - make_interval(hours => $3)
+              AND duplicada_de IS NULL
+              AND status NOT IN ('CANCELADO', 'DUPLICADO')
+            ORDER BY created_at
+            "#
+        )
+        .bind(telefone_normalizado)
+        .bind(municipio)
+        .bind(JANELA_HORAS)
+        .fetch_all(db)
+        .await?;
+
+    Ok(candidatos
+        .into_iter()
+        .find(|(_, outro)| nomes_parecidos(nome, outro))
+        .map(|(uid, _)| uid))
+}
+
+// Confirma a duplicata: a solicitacao vai para DUPLICADO apontando para o original,
+// as atribuicoes em aberto sao canceladas e os votos passam para o original
+#[utoipa::path(
+    post, path = "/solicitacoes/{uid}/mesclar", tag = "solicitacoes",
+    params(("uid" = String, Path)),
+    request_body = MesclarSolicitacao,
+    responses((status = 200, body = Solicitacao))
+)]
+pub async fn mesclar_solicitacao(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+    Json(payload): Json<MesclarSolicitacao>,
+) -> Result<Json<Solicitacao>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Coordenador)?;
+    payload.validado()?;
+
+    let mut tx = state.db.begin().await?;
+
+    let marcada: Option<String> =
+        sqlx::query_scalar("SELECT duplicada_de FROM solicitacoes WHERE uid = $1 AND app_id = $2 FOR UPDATE")
+            .bind(&uid)
+            .bind(app_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+    let original = payload.original_uid
+        .or(marcada)
+        .ok_or_else(|| AppError::Validation("original_uid e obrigatorio para solicitacoes nao marcadas como duplicadas".into()))?;
+
+    if original == uid {
+        return Err(AppError::Validation("uma solicitacao nao pode ser mesclada nela mesma".into()));
+    }
+
+    // o original pode ser de outro app, mas nao pode ter sido mesclado tambem
+    let (status_original, app_original): (String, String) =
+        sqlx::query_as("SELECT status, app_id FROM solicitacoes WHERE uid = $1")
+            .bind(&original)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+    if status_original == StatusSolicitacao::Duplicado.as_str() {
+        return Err(AppError::Conflict("o original ja foi mesclado em outra solicitacao".into()));
+    }
+
+    let observacao = format!("mesclada em {original}");
+    alterar_status_solicitacao(&mut tx, &current, app_id, &uid, StatusSolicitacao::Duplicado, Some(&observacao)).await?;
+
+    sqlx::query("UPDATE solicitacoes SET duplicada_de = $2, mesclada_em = now() WHERE uid = $1")
+        .bind(&uid)
+        .bind(&original)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE atribuicoes SET status = 'CANCELADA', cancelada_em = now(), updated_at = now(),
+               observacao = COALESCE(observacao, $2)
+        WHERE solicitacao_id = $1 AND status IN ('PENDENTE', 'ACEITA', 'EM_ANDAMENTO')
+        "#
+    )
+    .bind(&uid)
+    .bind(&observacao)
+    .execute(&mut *tx)
+    .await?;
+
+    // quem ja votou nos dois conta uma vez so; o voto passa a ser do app do original
+    sqlx::query(
+        r#"
+        INSERT INTO votos_solicitacoes (app_id, solicitacao_id, user_id, created_at)
+        SELECT $3, $2, user_id, created_at FROM votos_solicitacoes WHERE solicitacao_id = $1
+        ON CONFLICT DO NOTHING
+        "#
+    )
+    .bind(&uid)
+    .bind(&original)
+    .bind(&app_original)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM votos_solicitacoes WHERE solicitacao_id = $1")
+        .bind(&uid)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("UPDATE solicitacoes SET votos = (SELECT count(*) FROM votos_solicitacoes WHERE solicitacao_id = uid) WHERE uid IN ($1, $2)")
+        .bind(&uid)
+        .bind(&original)
+        .execute(&mut *tx)
+        .await?;
+
+    let record = fetch_solicitacao(&mut *tx, &uid, app_id).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(record))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validacao::telefone_nacional;
+
+    #[test]
+    fn mesmo_telefone_em_formatos_diferentes() {
+        let chave = "32999991111";
+        for telefone in ["(32) 99999-1111", "+55 32 99999-1111", "5532999991111", "032 99999 1111", "+5532999991111"] {
+            assert_eq!(telefone_nacional(telefone), chave, "{telefone}");
+        }
+        assert_eq!(telefone_nacional("(32) 3333-4444"), "3233334444");
+        // curto demais para ter o 55 do pais
+        assert_eq!(telefone_nacional("55 3333"), "553333");
+        assert_eq!(telefone_nacional("sem numero"), "");
+    }
+
+    #[test]
+    fn nomes_parecidos_aceita_variacoes_da_mesma_pessoa() {
+        assert!(nomes_parecidos("Maria Silva", "maria da silva"));
+        assert!(nomes_parecidos("Maria Silva", "Maria Silva Souza"));
+        assert!(nomes_parecidos("José Antônio", "JOSE ANTONIO"));
+        assert!(nomes_parecidos("Conceição", "conceicao"));
+        // erro de digitacao
+        assert!(nomes_parecidos("Maria Slva", "Maria Silva"));
+    }
+
+    #[test]
+    fn nomes_parecidos_recusa_pessoas_diferentes() {
+        assert!(!nomes_parecidos("Maria Silva", "Joao Pereira"));
+        assert!(!nomes_parecidos("Ana Souza", "Ana Lima"));
+        assert!(!nomes_parecidos("", "Maria"));
+        // so preposicoes nao identificam ninguem
+        assert!(!nomes_parecidos("de", "da"));
+    }
+
+    #[test]
+    fn levenshtein_conta_edicoes() {
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("silva", "silva"), 0);
+        assert_eq!(levenshtein("silva", "slva"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+}
@@ -4,37 +4,174 @@ use axum::{
     Json,
 };
 use serde::Serialize;
+use sqlx::error::ErrorKind;
+use sqlx::postgres::PgDatabaseError;
 use thiserror::Error;
+use tracing::error;
+use utoipa::ToSchema;
 
+use crate::password::PasswordError;
+
+// Erro unico das rotas. O corpo e sempre
+// { "code": "...", "message": "...", "campos": [...] }
+// onde code e estavel (para os apps tratarem) e message e para pessoas.
+// Detalhes do banco vao apenas para o log.
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("Database error: {0}")]
-    Database(#[from] sqlx::Error),
+    Database(sqlx::Error),
 
+    // sem credencial ou credencial invalida
     #[error("Unauthorized")]
     Unauthorized,
 
+    // autenticado, mas sem permissao para a operacao
+    #[error("Forbidden")]
+    Forbidden,
+
+    #[error("Not found")]
+    NotFound,
+
+    #[error("{0}")]
+    Conflict(String),
+
+    // requisicao mal formada (json invalido, query string, path)
+    #[error("{0}")]
+    BadRequest(String),
+
+    #[error("{0}")]
+    Validation(String),
+
+    // um erro por campo invalido do payload
+    #[error("dados invalidos")]
+    Campos(Vec<ErroCampo>),
+
     #[error("Internal server error")]
     Internal,
 }
 
-#[derive(Serialize)]
-struct ErrorResponse {
-    message: String,
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ErroCampo {
+    pub campo: String,
+    pub mensagem: String,
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let status = match self {
+#[derive(Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub code: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub campos: Vec<ErroCampo>,
+}
+
+impl AppError {
+    pub fn status(&self) -> StatusCode {
+        match self {
             AppError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::Unauthorized => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden => StatusCode::FORBIDDEN,
+            AppError::NotFound => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Campos(_) => StatusCode::UNPROCESSABLE_ENTITY,
             AppError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    // codigo estavel para os apps; nao mudar os valores existentes
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Database(_) | AppError::Internal => "internal_error",
+            AppError::Unauthorized => "unauthorized",
+            AppError::Forbidden => "forbidden",
+            AppError::NotFound => "not_found",
+            AppError::Conflict(_) => "conflict",
+            AppError::BadRequest(_) => "bad_request",
+            AppError::Validation(_) | AppError::Campos(_) => "validation_failed",
+        }
+    }
+
+    pub fn campo(campo: &str, mensagem: impl Into<String>) -> Self {
+        AppError::Campos(vec![ErroCampo { campo: campo.to_string(), mensagem: mensagem.into() }])
+    }
+}
+
+// mensagens das restricoes unique conhecidas
+fn mensagem_conflito(restricao: Option<&str>) -> String {
+    match restricao {
+        Some("users_email_application_key") => "email ja cadastrado neste app".into(),
+        Some("uq_atribuicoes_solicitacao_voluntario_ativa") => "voluntario ja atribuido a esta solicitacao".into(),
+        Some("uq_voluntarios_app_user") => "usuario ja tem cadastro de voluntario neste app".into(),
+        Some("app_pkey") => "app ja cadastrado".into(),
+        _ => "registro ja existe".into(),
+    }
+}
+
+// coluna a partir do nome da restricao: <tabela>_<coluna>_fkey
+fn coluna_da_restricao(tabela: Option<&str>, restricao: Option<&str>) -> String {
+    let restricao = restricao.unwrap_or_default();
+    let sem_tabela = tabela
+        .and_then(|t| restricao.strip_prefix(t))
+        .and_then(|r| r.strip_prefix('_'))
+        .unwrap_or(restricao);
+    sem_tabela.strip_suffix("_fkey").unwrap_or(sem_tabela).to_string()
+}
+
+// violacoes de restricao viram erros do cliente; o resto e erro interno
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        let sqlx::Error::Database(db) = &e else {
+            return match e {
+                sqlx::Error::RowNotFound => AppError::NotFound,
+                e => AppError::Database(e),
+            };
+        };
+
+        match db.kind() {
+            ErrorKind::UniqueViolation => AppError::Conflict(mensagem_conflito(db.constraint())),
+            ErrorKind::ForeignKeyViolation => {
+                AppError::campo(&coluna_da_restricao(db.table(), db.constraint()), "referencia inexistente")
+            }
+            ErrorKind::NotNullViolation => {
+                let coluna = db
+                    .try_downcast_ref::<PgDatabaseError>()
+                    .and_then(|pg| pg.column())
+                    .unwrap_or("campo");
+                AppError::campo(coluna, "campo obrigatorio")
+            }
+            ErrorKind::CheckViolation => AppError::Validation("valor invalido".into()),
+            _ => AppError::Database(e),
+        }
+    }
+}
+
+impl From<PasswordError> for AppError {
+    fn from(_: PasswordError) -> Self {
+        AppError::Internal
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        if let AppError::Database(e) = &self {
+            error!("Database error: {:?}", e);
+        }
+
+        let status = self.status();
+        let code = self.code();
+        let message = match &self {
+            // nunca expor o texto do banco
+            AppError::Database(_) => AppError::Internal.to_string(),
+            e => e.to_string(),
+        };
+        let campos = match self {
+            AppError::Campos(campos) => campos,
+            _ => Vec::new(),
         };
 
-        let body = Json(ErrorResponse {
-            message: self.to_string(),
-        });
+        let body = Json(ErrorResponse { code, message, campos });
 
         (status, body).into_response()
     }
-}
\ No newline at end of file
+}
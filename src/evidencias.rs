@@ -0,0 +1,227 @@
+use axum::body::Bytes;
+use axum::extract::multipart::MultipartRejection;
+use axum::extract::{Multipart, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use sha2::{Digest, Sha256};
+use tracing::error;
+use utoipa::ToSchema;
+
+use crate::armazenamento::ArmazenamentoError;
+use crate::error::AppError;
+use crate::extract::{Json, Path};
+use crate::handlers::{ensure_papel, AppState};
+use crate::middleware::CurrentUser;
+use crate::models::Evidencia;
+use crate::roles::Papel;
+use crate::validacao::Erros;
+
+// Arquivos de evidencia das denuncias (prints, comprovantes, pdf)
+//
+// Qualquer membro do app pode anexar arquivos as denuncias do app; apenas os
+// moderadores (coordenadores) do mesmo app listam e baixam. O tipo do arquivo
+// e reconhecido pelo conteudo, nao pelo Content-Type enviado pelo cliente.
+pub const TAMANHO_MAXIMO_ARQUIVO: usize = 10 * 1024 * 1024;
+
+// limite do corpo da requisicao de upload (varios arquivos)
+pub const TAMANHO_MAXIMO_UPLOAD: usize = 25 * 1024 * 1024;
+
+const EVIDENCIA_COLUNAS: &str =
+    "uid, denuncia_id, nome_arquivo, mime, tamanho, sha256, chave_armazenamento, created_by, created_at";
+
+fn tipo_do_arquivo(dados: &[u8]) -> Option<&'static str> {
+    match dados {
+        [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some("image/png"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        [b'%', b'P', b'D', b'F', b'-', ..] => Some("application/pdf"),
+        _ => None,
+    }
+}
+
+// nome seguro para o Content-Disposition
+fn nome_seguro(nome: &str) -> String {
+    let nome: String = nome
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || ".-_".contains(c) { c } else { '_' })
+        .take(100)
+        .collect();
+    if nome.trim_matches(['.', '_']).is_empty() { "evidencia".into() } else { nome }
+}
+
+fn erro_armazenamento(e: ArmazenamentoError) -> AppError {
+    error!("Storage error: {:?}", e);
+    AppError::Internal
+}
+
+// corpo multipart do upload, apenas para a documentacao OpenAPI
+#[derive(ToSchema)]
+pub struct UploadEvidencias {
+    // um ou mais arquivos; o nome do campo e livre
+    #[schema(value_type = Vec<String>, format = Binary)]
+    pub arquivos: Vec<Vec<u8>>,
+}
+
+struct Arquivo {
+    nome: String,
+    mime: &'static str,
+    dados: Bytes,
+}
+
+#[utoipa::path(
+    post, path = "/denuncias/{uid}/evidencias", tag = "denuncias",
+    params(("uid" = String, Path)),
+    request_body(content = UploadEvidencias, content_type = "multipart/form-data"),
+    responses((status = 200, body = Vec<Evidencia>))
+)]
+pub async fn upload_evidencias(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> Result<Json<Vec<Evidencia>>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
+    let mut multipart = multipart.map_err(|e| AppError::BadRequest(e.body_text()))?;
+
+    sqlx::query_scalar::<_, i32>("SELECT 1 FROM denuncias WHERE uid = $1 AND app_id = $2")
+        .bind(&uid)
+        .bind(app_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    // le e valida todos os arquivos antes de gravar qualquer um
+    let mut erros = Erros::default();
+    let mut arquivos = Vec::new();
+    while let Some(campo) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::Validation(format!("upload invalido: {}", e.body_text())))?
+    {
+        let Some(nome) = campo.file_name().map(str::to_string) else { continue };
+        let dados = campo
+            .bytes()
+            .await
+            .map_err(|e| AppError::Validation(format!("upload invalido: {}", e.body_text())))?;
+
+        if dados.is_empty() {
+            erros.campo(&nome, "arquivo vazio");
+        } else if dados.len() > TAMANHO_MAXIMO_ARQUIVO {
+            erros.campo(&nome, format!("arquivo maior que {} MB", TAMANHO_MAXIMO_ARQUIVO / 1024 / 1024));
+        } else {
+            match tipo_do_arquivo(&dados) {
+                Some(mime) => arquivos.push(Arquivo { nome, mime, dados }),
+                None => erros.campo(&nome, "tipo de arquivo nao aceito (use png, jpeg, gif, webp ou pdf)"),
+            }
+        }
+    }
+    if arquivos.is_empty() && erros.vazio() {
+        erros.campo("arquivo", "envie ao menos um arquivo");
+    }
+    erros.into_result()?;
+
+    let sql = format!(
+        r#"
+        INSERT INTO denuncias_evidencias
+        (denuncia_id, app_id, nome_arquivo, mime, tamanho, sha256, chave_armazenamento, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (denuncia_id, sha256) DO UPDATE SET sha256 = EXCLUDED.sha256
+        RETURNING {EVIDENCIA_COLUNAS}
+        "#
+    );
+
+    let mut registros = Vec::with_capacity(arquivos.len());
+    for arquivo in arquivos {
+        let sha256 = format!("{:x}", Sha256::digest(&arquivo.dados));
+        let chave = format!("{app_id}/{uid}/{sha256}");
+        let tamanho = arquivo.dados.len() as i64;
+
+        // o mesmo conteudo gera a mesma chave, entao reenviar e idempotente
+        state.armazenamento.salvar(&chave, arquivo.dados).await.map_err(erro_armazenamento)?;
+
+        let registro =
+            sqlx::query_as::<_, Evidencia>(&sql)
+                .bind(&uid)
+                .bind(app_id)
+                .bind(nome_seguro(&arquivo.nome))
+                .bind(arquivo.mime)
+                .bind(tamanho)
+                .bind(&sha256)
+                .bind(&chave)
+                .bind(&current.user_id)
+                .fetch_one(&state.db)
+                .await?;
+        registros.push(registro);
+    }
+
+    Ok(Json(registros))
+}
+
+#[utoipa::path(
+    get, path = "/denuncias/{uid}/evidencias", tag = "denuncias",
+    params(("uid" = String, Path)),
+    responses((status = 200, body = Vec<Evidencia>))
+)]
+pub async fn list_evidencias(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<Json<Vec<Evidencia>>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Coordenador)?;
+
+    let sql = format!(
+        "SELECT {EVIDENCIA_COLUNAS} FROM denuncias_evidencias WHERE denuncia_id = $1 AND app_id = $2 ORDER BY created_at"
+    );
+
+    let rows =
+        sqlx::query_as::<_, Evidencia>(&sql)
+            .bind(&uid)
+            .bind(app_id)
+            .fetch_all(&state.db)
+            .await?;
+
+    Ok(Json(rows))
+}
+
+#[utoipa::path(
+    get, path = "/denuncias/{uid}/evidencias/{evidencia}", tag = "denuncias",
+    params(("uid" = String, Path), ("evidencia" = String, Path)),
+    responses((status = 200, description = "conteudo do arquivo", content_type = "application/octet-stream"))
+)]
+pub async fn download_evidencia(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path((uid, evidencia)): Path<(String, String)>,
+) -> Result<Response, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Coordenador)?;
+
+    let sql = format!(
+        "SELECT {EVIDENCIA_COLUNAS} FROM denuncias_evidencias WHERE uid = $1 AND denuncia_id = $2 AND app_id = $3"
+    );
+
+    let registro =
+        sqlx::query_as::<_, Evidencia>(&sql)
+            .bind(&evidencia)
+            .bind(&uid)
+            .bind(app_id)
+            .fetch_optional(&state.db)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+    let dados = state.armazenamento.ler(&registro.chave_armazenamento).await.map_err(erro_armazenamento)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, registro.mime),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", registro.nome_arquivo)),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".into()),
+            (header::CACHE_CONTROL, "private, no-store".into()),
+        ],
+        dados,
+    ).into_response())
+}
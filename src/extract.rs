@@ -0,0 +1,100 @@
+use axum::extract::rejection::{JsonRejection, PathRejection, QueryRejection};
+use axum::extract::{FromRequest, FromRequestParts, OptionalFromRequest, Request};
+use axum::http::request::Parts;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+
+use crate::error::AppError;
+
+// Extratores do axum com as rejeicoes convertidas em AppError, para que
+// json mal formado, query string e path invalidos respondam no mesmo formato
+// dos demais erros
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+pub struct Query<T>(pub T);
+
+pub struct Path<T>(pub T);
+
+impl From<JsonRejection> for AppError {
+    fn from(rejeicao: JsonRejection) -> Self {
+        // json valido com campos ausentes ou de tipo errado
+        if rejeicao.status() == StatusCode::UNPROCESSABLE_ENTITY {
+            AppError::Validation(rejeicao.body_text())
+        } else {
+            AppError::BadRequest(rejeicao.body_text())
+        }
+    }
+}
+
+impl From<QueryRejection> for AppError {
+    fn from(rejeicao: QueryRejection) -> Self {
+        AppError::BadRequest(rejeicao.body_text())
+    }
+}
+
+impl From<PathRejection> for AppError {
+    fn from(rejeicao: PathRejection) -> Self {
+        AppError::BadRequest(rejeicao.body_text())
+    }
+}
+
+impl<T, S> FromRequest<S> for Json<T>
+where
+    axum::Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::Json(valor) = axum::Json::<T>::from_request(req, state).await?;
+        Ok(Json(valor))
+    }
+}
+
+// corpo opcional (ex: logout sem payload)
+impl<T, S> OptionalFromRequest<S> for Json<T>
+where
+    axum::Json<T>: OptionalFromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Option<Self>, Self::Rejection> {
+        let valor = <axum::Json<T> as OptionalFromRequest<S>>::from_request(req, state).await?;
+        Ok(valor.map(|axum::Json(valor)| Json(valor)))
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+impl<T, S> FromRequestParts<S> for Query<T>
+where
+    axum::extract::Query<T>: FromRequestParts<S, Rejection = QueryRejection>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Query(valor) = axum::extract::Query::<T>::from_request_parts(parts, state).await?;
+        Ok(Query(valor))
+    }
+}
+
+impl<T, S> FromRequestParts<S> for Path<T>
+where
+    axum::extract::Path<T>: FromRequestParts<S, Rejection = PathRejection>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Path(valor) = axum::extract::Path::<T>::from_request_parts(parts, state).await?;
+        Ok(Path(valor))
+    }
+}
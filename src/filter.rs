@@ -1,5 +1,134 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::{Postgres, QueryBuilder};
+use utoipa::IntoParams;
+
+// Filtros aceitos na query string das listagens
+// ex: /pontos?municipio=Juiz de Fora&bairro=Centro&q=agua
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct Filter {
     pub app_id: Option<String>,
-    pub page: Option<i64>,
-    pub per_page: Option<i64>,
-}
\ No newline at end of file
+    pub municipio: Option<String>,
+    pub bairro: Option<String>,
+    pub categoria: Option<String>,
+    pub tipo: Option<String>,
+    pub status: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub q: Option<String>,
+    // inclui os dados dos apps publicos que optaram por compartilhar (visao federada)
+    pub federado: Option<bool>,
+    // inclui os pontos em quarentena (somente coordenadores)
+    pub bloqueados: Option<bool>,
+}
+
+// Quais filtros cada tabela suporta; filtros de colunas inexistentes sao ignorados
+pub struct Colunas {
+    pub tabela: &'static str,
+    pub filtros: &'static [&'static str],
+    pub busca: &'static [&'static str],
+    // tabela com quarentena (bloqueado_em): fora das listagens salvo com bloqueados=true
+    pub quarentena: bool,
+}
+
+pub const PONTOS: Colunas = Colunas {
+    tabela: "pontos",
+    filtros: &["municipio", "bairro", "categoria", "tipo"],
+    busca: &["nome", "itens"],
+    quarentena: true,
+};
+
+pub const VOLUNTARIOS: Colunas = Colunas {
+    tabela: "voluntarios",
+    filtros: &["municipio", "categoria"],
+    busca: &["nome", "mensagem"],
+    quarentena: false,
+};
+
+pub const SOLICITACOES: Colunas = Colunas {
+    tabela: "solicitacoes",
+    filtros: &["municipio", "categoria", "status"],
+    busca: &["nome", "mensagem"],
+    quarentena: false,
+};
+
+pub const DENUNCIAS: Colunas = Colunas {
+    tabela: "denuncias",
+    filtros: &["tipo", "status"],
+    busca: &["memorando"],
+    quarentena: false,
+};
+
+impl Filter {
+    fn valor(&self, coluna: &str) -> Option<&String> {
+        match coluna {
+            "municipio" => self.municipio.as_ref(),
+            "bairro" => self.bairro.as_ref(),
+            "categoria" => self.categoria.as_ref(),
+            "tipo" => self.tipo.as_ref(),
+            "status" => self.status.as_ref(),
+            _ => None,
+        }
+    }
+
+    // Acrescenta o WHERE na query; usado tanto no COUNT quanto na listagem
+    // para que o total da paginacao reflita o filtro.
+    // Sempre restringe ao app de quem chama; com federado=true entram tambem
+    // os apps publicos (tipo 'public') que ativaram o compartilhamento
+    pub fn push_where(&self, qb: &mut QueryBuilder<'_, Postgres>, colunas: &Colunas, app_atual: &str) {
+        let tabela = colunas.tabela;
+        qb.push(format!(" WHERE ({tabela}.app_id = "))
+            .push_bind(app_atual.to_string());
+
+        if self.federado.unwrap_or(false) {
+            qb.push(format!(
+                " OR {tabela}.app_id IN (SELECT uid FROM app WHERE tipo = 'public' AND federado AND ativo)"
+            ));
+        }
+        qb.push(")");
+
+        if let Some(app_id) = &self.app_id {
+            qb.push(format!(" AND {tabela}.app_id = "))
+                .push_bind(app_id.clone());
+        }
+
+        if colunas.quarentena && !self.bloqueados.unwrap_or(false) {
+            qb.push(format!(" AND {tabela}.bloqueado_em IS NULL"));
+        }
+
+        for coluna in colunas.filtros {
+            if let Some(valor) = self.valor(coluna) {
+                qb.push(format!(" AND lower({tabela}.{coluna}) = lower("))
+                    .push_bind(valor.clone())
+                    .push(")");
+            }
+        }
+
+        if let Some(after) = self.created_after {
+            qb.push(format!(" AND {tabela}.created_at >= ")).push_bind(after);
+        }
+
+        if let Some(before) = self.created_before {
+            qb.push(format!(" AND {tabela}.created_at < ")).push_bind(before);
+        }
+
+        if let Some(q) = self.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            let padrao = format!("%{}%", escape_like(q));
+            qb.push(" AND (");
+            for (i, coluna) in colunas.busca.iter().enumerate() {
+                if i > 0 {
+                    qb.push(" OR ");
+                }
+                qb.push(format!("{tabela}.{coluna} ILIKE "))
+                    .push_bind(padrao.clone());
+            }
+            qb.push(")");
+        }
+    }
+}
+
+// % e _ digitados pelo usuario devem ser literais no ILIKE
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
@@ -0,0 +1,80 @@
+// Coordenadas geograficas (WGS84) dos pontos
+//
+// O campo `gps` chega como texto livre "lat,lon" (ex: "-21.7622,-43.3430");
+// aqui ele e validado e convertido para latitude/longitude numericas.
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordenadas {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Coordenadas {
+    pub fn new(latitude: f64, longitude: f64) -> Result<Self, String> {
+        if !latitude.is_finite() || !(-90.0..=90.0).contains(&latitude) {
+            return Err(format!("latitude fora do intervalo [-90, 90]: {latitude}"));
+        }
+        if !longitude.is_finite() || !(-180.0..=180.0).contains(&longitude) {
+            return Err(format!("longitude fora do intervalo [-180, 180]: {longitude}"));
+        }
+        Ok(Coordenadas { latitude, longitude })
+    }
+
+    // formato gravado na coluna gps
+    pub fn to_gps(self) -> String {
+        format!("{},{}", self.latitude, self.longitude)
+    }
+}
+
+// distancia em linha reta (haversine), em km
+pub fn distancia_km(a: Coordenadas, b: Coordenadas) -> f64 {
+    const RAIO_TERRA_KM: f64 = 6371.0;
+
+    let dlat = (b.latitude - a.latitude).to_radians();
+    let dlon = (b.longitude - a.longitude).to_radians();
+    let h = (dlat / 2.0).sin().powi(2)
+        + a.latitude.to_radians().cos() * b.latitude.to_radians().cos() * (dlon / 2.0).sin().powi(2);
+
+    2.0 * RAIO_TERRA_KM * h.sqrt().asin()
+}
+
+pub fn parse_gps(gps: &str) -> Result<Coordenadas, String> {
+    let (lat, lon) = gps
+        .split_once(',')
+        .ok_or_else(|| format!("gps deve estar no formato \"lat,lon\": {gps}"))?;
+
+    let latitude: f64 = lat
+        .trim()
+        .parse()
+        .map_err(|_| format!("latitude invalida: {}", lat.trim()))?;
+    let longitude: f64 = lon
+        .trim()
+        .parse()
+        .map_err(|_| format!("longitude invalida: {}", lon.trim()))?;
+
+    Coordenadas::new(latitude, longitude)
+}
+
+// Solicitacoes nao tem coluna de gps; as coordenadas vem do metadados, em um destes formatos:
+// { "latitude": -21.7, "longitude": -43.3 }
+// { "localizacao": { "latitude": .., "longitude": .. } } (ou "localizacao_aproximada")
+// { "gps": "-21.7,-43.3" }
+pub fn coordenadas_de_metadados(metadados: &Value) -> Option<Coordenadas> {
+    let lat_lon = |v: &Value| {
+        let latitude = v.get("latitude")?.as_f64()?;
+        let longitude = v.get("longitude")?.as_f64()?;
+        Coordenadas::new(latitude, longitude).ok()
+    };
+
+    lat_lon(metadados)
+        .or_else(|| metadados.get("localizacao").and_then(lat_lon))
+        .or_else(|| metadados.get("localizacao_aproximada").and_then(lat_lon))
+        .or_else(|| {
+            metadados
+                .get("gps")
+                .and_then(Value::as_str)
+                .and_then(|gps| parse_gps(gps).ok())
+        })
+}
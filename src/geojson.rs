@@ -0,0 +1,69 @@
+use axum::{
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::geo::Coordenadas;
+
+// GeoJSON (RFC 7946) minimo para plotar pontos em Leaflet/MapLibre
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeatureCollection {
+    #[serde(rename = "type")]
+    pub tipo: &'static str,
+    pub features: Vec<Feature>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Feature {
+    #[serde(rename = "type")]
+    pub tipo: &'static str,
+    pub geometry: Geometry,
+    pub properties: Value,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Geometry {
+    #[serde(rename = "type")]
+    pub tipo: &'static str,
+    // atencao: GeoJSON usa [longitude, latitude]
+    pub coordinates: [f64; 2],
+}
+
+impl FeatureCollection {
+    pub fn from_items<T: Serialize>(
+        items: impl IntoIterator<Item = (Coordenadas, T)>,
+    ) -> Result<Self, serde_json::Error> {
+        let features = items
+            .into_iter()
+            .map(|(coordenadas, item)| {
+                Ok(Feature {
+                    tipo: "Feature",
+                    geometry: Geometry {
+                        tipo: "Point",
+                        coordinates: [coordenadas.longitude, coordenadas.latitude],
+                    },
+                    properties: serde_json::to_value(item)?,
+                })
+            })
+            .collect::<Result<Vec<_>, serde_json::Error>>()?;
+
+        Ok(FeatureCollection {
+            tipo: "FeatureCollection",
+            features,
+        })
+    }
+}
+
+impl IntoResponse for FeatureCollection {
+    fn into_response(self) -> Response {
+        (
+            [(header::CONTENT_TYPE, "application/geo+json")],
+            Json(self),
+        )
+            .into_response()
+    }
+}
@@ -1,48 +1,68 @@
 use axum::Extension;
-use axum::extract::Query;
 use axum::http::StatusCode;
-use axum::{Json, extract::State};
+use axum::extract::State;
+use sqlx::{PgConnection, PgExecutor, QueryBuilder};
+use std::sync::Arc;
 use tracing::info;
 use uuid::Uuid; 
 
-use crate::db::{DbPool, internal_error};
+use crate::api_keys::{create_key, ApiKeyCache, TipoChave};
+use crate::armazenamento::Armazenamento;
+use crate::db::DbPool;
+use crate::denuncias::{self, ConfigBloqueio};
+use crate::duplicatas;
 use crate::error::AppError;
+use crate::extract::{Json, Path, Query};
+use crate::jwt::JwtConfig;
+use crate::filter::{self, Filter};
+use crate::geo::{coordenadas_de_metadados, parse_gps, Coordenadas};
+use crate::geojson::FeatureCollection;
 use crate::middleware::{CurrentUser, UserKind};
-use crate::models::{AppStruct, CreateUser, Denuncia, PingResponse, Ponto, Solicitacao, User, Voluntario};
+use crate::models::{AppCriado, AppStruct, CreateUser, Denuncia, HistoricoSolicitacao, ItemContagem, ItemPonto, NecessidadeItem, NecessidadesBairro, PapelUsuario, PingResponse, Ponto, PontoProximo, Solicitacao, User, Voluntario};
+use crate::roles::Papel;
 use crate::pagination::{PaginatedResponse, Pagination};
 use crate::password::hash_password;
-use crate::payloads::{NewDenuncia, NewPonto, NewSolicitacao, NewVoluntario};
-use sqlx;
+use crate::payloads::{AjusteEstoque, NearbyQuery, NewApp, NewDenuncia, NewPonto, NewSolicitacao, NewVoluntario, UpdateApp, UpdateItemPonto, UpdatePapel, UpdatePonto, UpdateStatusSolicitacao};
+use crate::validacao::{normalizar_chave_pix, normalizar_cnpj, telefone_nacional, Erros, Validar};
+use crate::workflow::StatusSolicitacao;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: DbPool,
+    pub jwt: Arc<JwtConfig>,
+    pub api_keys: ApiKeyCache,
+    pub armazenamento: Arc<Armazenamento>,
+    pub bloqueio: ConfigBloqueio,
 }
 
+#[utoipa::path(
+    get, path = "/ping", tag = "status",
+    responses((status = 200, body = PingResponse)),
+    security(())
+)]
 pub async fn ping() -> Json<PingResponse> {
     Json(PingResponse {
         status: "ok".to_string(),
     })
 }
 
+#[utoipa::path(
+    post, path = "/register", tag = "auth",
+    request_body = CreateUser,
+    responses((status = 200, body = User)),
+    security(())
+)]
 pub async fn create_user(
     State(state): State<AppState>,
     Json(payload): Json<CreateUser>,
-) -> Result<Json<User>, (StatusCode, String)> {
-    //validacao minima de segurança
-    if payload.senha.len() < 6 {
-        return Err((StatusCode::BAD_REQUEST, "Weak password".into()));
-    }
+) -> Result<Json<User>, AppError> {
+    payload.validado()?;
 
     let id = Uuid::new_v4();
-    let password_hash = hash_password(&payload.senha).map_err(|_| {
-        (
-            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-            "Password hashing failed".to_string(),
-        )
-    })?;
+    let password_hash = hash_password(&payload.senha)?;
 
-    let res = sqlx::query_as::<_, User>(
+    // email repetido no mesmo app vira 409 (users_email_application_key)
+    let user = sqlx::query_as::<_, User>(
         "INSERT INTO users (id, nome, email, app_id, senha) 
     VALUES ($1, $2, $3, $4, $5) 
     RETURNING id, nome, email, senha",
@@ -50,232 +70,725 @@ pub async fn create_user(
     .bind(id)
     .bind(&payload.nome)
     .bind(&payload.email)
-    .bind(&payload.app_id.unwrap_or_else(|| "DEMONSTRA".into()))
+    .bind(payload.app_id.unwrap_or_else(|| "DEMONSTRA".into()))
     .bind(&password_hash)
     .fetch_one(&state.db)
-    .await;
-
-    match res {
-        Ok(user) => Ok(Json(user)),
-        Err(err) => {
-            // basic error mapping; improve in Part 3
-            let msg = format!("DB error: {}", err);
-            Err((StatusCode::INTERNAL_SERVER_ERROR, msg))
-        }
+    .await?;
+
+    Ok(Json(user))
+}
+
+// App do contexto da requisicao, exigindo um papel minimo do usuario nele
+pub fn ensure_papel(current: &CurrentUser, minimo: Papel) -> Result<&str, AppError> {
+    let app_id = current.app_id.as_deref().ok_or(AppError::Forbidden)?;
+    match current.papel {
+        Some(papel) if papel >= minimo => Ok(app_id),
+        _ => Err(AppError::Forbidden),
     }
 }
 
-fn ensure_app(current: &CurrentUser) -> Result<&str, AppError> {
-    if current.kind != UserKind::App {
-        return Err(AppError::Unauthorized);
+// pontos em quarentena exibem a chave do golpe: so a moderacao lista
+fn ensure_filtro_permitido(current: &CurrentUser, filter: &Filter) -> Result<(), AppError> {
+    if filter.bloqueados.unwrap_or(false) {
+        ensure_papel(current, Papel::Coordenador)?;
     }
-    Ok(&current.user_id)
+    Ok(())
 }
 
+#[utoipa::path(
+    get, path = "/users", tag = "users",
+    responses((status = 200, body = Vec<User>))
+)]
 pub async fn list_users(
     State(state): State<AppState>,
     Extension(current_user): Extension<CurrentUser>,
-) -> Result<Json<Vec<User>>, (StatusCode, String)> {
+) -> Result<Json<Vec<User>>, AppError> {
 
-    //Apenas aplicativos podem ver usuarios
-    if current_user.kind != UserKind::App {
-        return Err((StatusCode::UNAUTHORIZED, "".to_string()));
-    }
+    //apenas coordenadores do app podem ver usuarios, e so os do proprio app
+    let app_id = ensure_papel(&current_user, Papel::Coordenador)?;
+
+    let users = sqlx::query_as::<_, User>("SELECT id, nome, email, '*****' as senha FROM users where app_id = $1")
+    .bind(app_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(users))
+}
+
+// papeis dos usuarios no app
+#[utoipa::path(
+    get, path = "/papeis", tag = "users",
+    responses((status = 200, body = Vec<PapelUsuario>))
+)]
+pub async fn list_papeis(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+) -> Result<Json<Vec<PapelUsuario>>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Admin)?;
 
-    //aplicativos so podem ver os proprios usuarios 
-    if let Ok(authenticated_add) = ensure_app(&current_user) {
-        let res = sqlx::query_as::<_, User>("SELECT id, nome, email, '*****' as senha FROM users where app_id = $1")
-        .bind(authenticated_add)
+    let rows =
+        sqlx::query_as::<_, PapelUsuario>(
+            r#"
+            select papeis_usuarios.user_id, users.nome, users.email, papel, papeis_usuarios.created_at, papeis_usuarios.created_by
+            from papeis_usuarios join users on user_id = users.id
+            where papeis_usuarios.app_id = $1
+            order by users.nome
+            "#
+        )
+        .bind(app_id)
         .fetch_all(&state.db)
-        .await;
-
-        match res {
-            Ok(users) => Ok(Json(users)),
-            Err(err) => Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("DB error: {}", err),
-            )),
-        }
-    } else { Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("UNAUTHORIZED"),
-            ))}
+        .await?;
+
+    Ok(Json(rows))
+}
+
+// define o papel de um usuario no app; vale a partir do proximo login ou renovacao do token
+#[utoipa::path(
+    put, path = "/users/{id}/papel", tag = "users",
+    params(("id" = String, Path)),
+    request_body = UpdatePapel,
+    responses((status = 200, body = PapelUsuario))
+)]
+pub async fn update_papel(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(user_id): Path<String>,
+    Json(payload): Json<UpdatePapel>,
+) -> Result<Json<PapelUsuario>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Admin)?;
+
+    let record =
+        sqlx::query_as::<_, PapelUsuario>(
+            r#"
+            WITH upserted AS (
+                INSERT INTO papeis_usuarios (user_id, app_id, papel, created_by)
+                SELECT id, $2, $3, $4 FROM users WHERE id = $1 AND app_id = $2
+                ON CONFLICT (user_id, app_id) DO UPDATE SET papel = EXCLUDED.papel, created_by = EXCLUDED.created_by
+                RETURNING *
+            )
+            select upserted.user_id, users.nome, users.email, papel, upserted.created_at, upserted.created_by
+            from upserted join users on user_id = users.id
+            "#
+        )
+        .bind(&user_id)
+        .bind(app_id)
+        .bind(payload.papel.as_str())
+        .bind(&current.user_id)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(record))
+}
+
+#[utoipa::path(
+    delete, path = "/users/{id}/papel", tag = "users",
+    params(("id" = String, Path)),
+    responses((status = 204))
+)]
+pub async fn delete_papel(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(user_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Admin)?;
+
+    let res = sqlx::query("DELETE FROM papeis_usuarios WHERE user_id = $1 AND app_id = $2")
+        .bind(&user_id)
+        .bind(app_id)
+        .execute(&state.db)
+        .await?;
+
+    if res.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
+// tambem servida sem autenticacao em /apps/view
+#[utoipa::path(
+    get, path = "/apps", tag = "apps",
+    params(Pagination),
+    responses((status = 200, body = PaginatedResponse<AppStruct>))
+)]
 pub async fn list_apps(
     State(state): State<AppState>,
-    Extension(_current_user): Extension<CurrentUser>,
+    current_user: Option<Extension<CurrentUser>>,
     Query(pagination): Query<Pagination>,
-) -> Result<Json<PaginatedResponse<AppStruct>>, (StatusCode, String)> {
+) -> Result<Json<PaginatedResponse<AppStruct>>, AppError> {
 
     let (limit, offset) = pagination.limit_offset();
     info!("{}{}", limit, offset);
 
+    // apps desativados so aparecem para super admins
+    let todos = current_user.is_some_and(|Extension(c)| c.super_admin);
+
     let total: (i64,) =
-        sqlx::query_as("SELECT COUNT(*) FROM app") 
+        sqlx::query_as("SELECT COUNT(*) FROM app WHERE ativo OR $1") 
+            .bind(todos)
             .fetch_one(&state.db)
-            .await
-            .map_err(internal_error)?;
+            .await?;
 
     let rows =
         sqlx::query_as::<_, AppStruct>(
-            "SELECT nome,
+            "SELECT uid,
+                nome,
                 responsavel,
                 repositorio,
                 tipo,
                 url,
                 metadados,
-                created_at FROM app ORDER BY created_at DESC LIMIT Coalesce($1, 50) OFFSET coalesce($2, 0)"
+                ativo,
+                federado,
+                created_at FROM app WHERE ativo OR $3 ORDER BY created_at DESC LIMIT Coalesce($1, 50) OFFSET coalesce($2, 0)"
         )
         .bind(limit)
         .bind(offset)
+        .bind(todos)
         .fetch_all(&state.db)
-        .await
-        .map_err(internal_error)?;
+        .await?;
 
     Ok(Json(PaginatedResponse {
         data: rows,
         total: total.0,
-        page: pagination.page.unwrap_or(1),
+        page: pagination.page(),
         per_page: limit,
     }))
 }
 
+fn ensure_super_admin(current: &CurrentUser) -> Result<(), AppError> {
+    if !current.super_admin {
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
+}
+
+fn validar_tipo_app(tipo: &str) -> Result<(), AppError> {
+    match tipo {
+        "public" | "private" => Ok(()),
+        outro => Err(AppError::Validation(format!("tipo de app invalido: {outro} (use public ou private)"))),
+    }
+}
+
+// Cadastra um app (tenant) e devolve, uma unica vez, a chave secreta inicial
+#[utoipa::path(
+    post, path = "/apps", tag = "apps",
+    request_body = NewApp,
+    responses((status = 200, body = AppCriado))
+)]
+pub async fn create_app(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Json(payload): Json<NewApp>,
+) -> Result<Json<AppCriado>, AppError> {
+
+    ensure_super_admin(&current)?;
+    payload.validado()?;
+
+    let tipo = payload.tipo.unwrap_or_else(|| "private".into());
+    validar_tipo_app(&tipo)?;
+
+    let mut tx = state.db.begin().await?;
+
+    let app =
+        sqlx::query_as::<_, AppStruct>(
+            r#"
+            INSERT INTO app (email, nome, responsavel, repositorio, tipo, url, metadados, federado)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING uid, nome, responsavel, repositorio, tipo, url, metadados, ativo, federado, created_at
+            "#
+        )
+        .bind(&payload.email)
+        .bind(&payload.nome)
+        .bind(&payload.responsavel)
+        .bind(&payload.repositorio)
+        .bind(&tipo)
+        .bind(&payload.url)
+        .bind(&payload.metadados)
+        .bind(payload.federado.unwrap_or(false))
+        .fetch_one(&mut *tx)
+        .await?;
+
+    let criada = create_key(&mut *tx, &app.uid, "inicial", TipoChave::Secret).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(AppCriado { app, chave: criada.chave }))
+}
+
+#[utoipa::path(
+    patch, path = "/apps/{uid}", tag = "apps",
+    params(("uid" = String, Path)),
+    request_body = UpdateApp,
+    responses((status = 200, body = AppStruct))
+)]
+pub async fn update_app(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+    Json(payload): Json<UpdateApp>,
+) -> Result<Json<AppStruct>, AppError> {
+
+    ensure_super_admin(&current)?;
+    payload.validado()?;
+
+    if let Some(tipo) = payload.tipo.as_deref() {
+        validar_tipo_app(tipo)?;
+    }
+
+    let record =
+        sqlx::query_as::<_, AppStruct>(
+            r#"
+            UPDATE app SET
+                email = COALESCE($2, email),
+                nome = COALESCE($3, nome),
+                responsavel = COALESCE($4, responsavel),
+                repositorio = COALESCE($5, repositorio),
+                tipo = COALESCE($6, tipo),
+                url = COALESCE($7, url),
+                metadados = COALESCE($8::json, metadados),
+                federado = COALESCE($9, federado),
+                updated_at = now()
+            WHERE uid = $1
+            RETURNING uid, nome, responsavel, repositorio, tipo, url, metadados, ativo, federado, created_at
+            "#
+        )
+        .bind(&uid)
+        .bind(&payload.email)
+        .bind(&payload.nome)
+        .bind(&payload.responsavel)
+        .bind(&payload.repositorio)
+        .bind(&payload.tipo)
+        .bind(&payload.url)
+        .bind(&payload.metadados)
+        .bind(payload.federado)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(record))
+}
+
+// Desativar bloqueia as chaves e as sessoes do app sem apagar nenhum dado
+#[utoipa::path(
+    post, path = "/apps/{uid}/disable", tag = "apps",
+    params(("uid" = String, Path)),
+    responses((status = 200, body = AppStruct))
+)]
+pub async fn disable_app(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<Json<AppStruct>, AppError> {
+    set_app_ativo(&state, &current, &uid, false).await
+}
+
+#[utoipa::path(
+    post, path = "/apps/{uid}/enable", tag = "apps",
+    params(("uid" = String, Path)),
+    responses((status = 200, body = AppStruct))
+)]
+pub async fn enable_app(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<Json<AppStruct>, AppError> {
+    set_app_ativo(&state, &current, &uid, true).await
+}
+
+async fn set_app_ativo(
+    state: &AppState,
+    current: &CurrentUser,
+    uid: &str,
+    ativo: bool,
+) -> Result<Json<AppStruct>, AppError> {
+
+    ensure_super_admin(current)?;
+
+    let record =
+        sqlx::query_as::<_, AppStruct>(
+            "UPDATE app SET ativo = $2, updated_at = now() WHERE uid = $1 RETURNING uid, nome, responsavel, repositorio, tipo, url, metadados, ativo, federado, created_at"
+        )
+        .bind(uid)
+        .bind(ativo)
+        .fetch_optional(&state.db)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(record))
+}
+
+#[utoipa::path(
+    get, path = "/pontos", tag = "pontos",
+    params(Pagination, Filter),
+    responses((status = 200, body = PaginatedResponse<Ponto>))
+)]
 pub async fn list_pontos(
     State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
     Query(pagination): Query<Pagination>,
-) -> Result<Json<PaginatedResponse<Ponto>>, (StatusCode, String)> {
+    Query(filter): Query<Filter>,
+) -> Result<Json<PaginatedResponse<Ponto>>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
+    ensure_filtro_permitido(&current, &filter)?;
 
     let (limit, offset) = pagination.limit_offset();
 
+    let mut count = QueryBuilder::new("SELECT COUNT(*) FROM pontos");
+    filter.push_where(&mut count, &filter::PONTOS, app_id);
+
     let total: (i64,) =
-        sqlx::query_as("SELECT COUNT(*) FROM pontos")
+        count.build_query_as()
             .fetch_one(&state.db)
-            .await
-            .map_err(internal_error)?;
+            .await?;
+
+    let mut query = QueryBuilder::new(
+        "select pontos.uid, pontos.nome, pontos.app_id, app.nome as origem, categoria, pontos.tipo, municipio, endereco, telefone, bairro, horarios, pontos.responsavel, pix, cnpj, itens, gps, latitude, longitude, pontos.metadados, pontos.votos, pontos.bloqueado_em, pontos.denuncia_bloqueio, pontos.created_at, pontos.created_by from pontos join app on app_id = app.uid"
+    );
+    filter.push_where(&mut query, &filter::PONTOS, app_id);
+    query.push(" ORDER BY pontos.created_at DESC LIMIT ").push_bind(limit)
+        .push(" OFFSET ").push_bind(offset);
 
     let rows =
-        sqlx::query_as::<_, Ponto>(
-            "select pontos.nome, pontos.app_id, app.nome as origem, categoria, pontos.tipo, municipio, endereco, telefone, bairro, horarios, pontos.responsavel, pix, cnpj, itens, gps, pontos.metadados, pontos.created_at, pontos.created_by from pontos join app on app_id = app.uid  ORDER BY created_at DESC LIMIT $1 OFFSET $2"
-        )
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&state.db)
-        .await
-        .map_err(internal_error)?;
+        query.build_query_as::<Ponto>()
+            .fetch_all(&state.db)
+            .await?;
 
     Ok(Json(PaginatedResponse {
         data: rows,
         total: total.0,
-        page: pagination.page.unwrap_or(1),
+        page: pagination.page(),
         per_page: limit,
     }))
 }
 
+#[utoipa::path(
+    get, path = "/voluntarios", tag = "voluntarios",
+    params(Pagination, Filter),
+    responses((status = 200, body = PaginatedResponse<Voluntario>))
+)]
 pub async fn list_voluntarios(
     State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
     Query(pagination): Query<Pagination>,
-) -> Result<Json<PaginatedResponse<Voluntario>>, (StatusCode, String)> {
+    Query(filter): Query<Filter>,
+) -> Result<Json<PaginatedResponse<Voluntario>>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
 
     let (limit, offset) = pagination.limit_offset();
 
+    let mut count = QueryBuilder::new("SELECT COUNT(*) FROM voluntarios");
+    filter.push_where(&mut count, &filter::VOLUNTARIOS, app_id);
+
     let total: (i64,) =
-        sqlx::query_as("SELECT COUNT(*) FROM voluntarios")
+        count.build_query_as()
             .fetch_one(&state.db)
-            .await
-            .map_err(internal_error)?;
+            .await?;
+
+    let mut query = QueryBuilder::new(
+        "select voluntarios.uid, voluntarios.nome, voluntarios.app_id, voluntarios.user_id, app.nome as origem, categoria, municipio, telefone, mensagem, voluntarios.metadados, voluntarios.created_by, voluntarios.created_at from voluntarios join app on app_id = app.uid"
+    );
+    filter.push_where(&mut query, &filter::VOLUNTARIOS, app_id);
+    query.push(" ORDER BY voluntarios.created_at DESC LIMIT ").push_bind(limit)
+        .push(" OFFSET ").push_bind(offset);
 
     let rows =
-        sqlx::query_as::<_, Voluntario>(
-            "select voluntarios.uid, voluntarios.nome, voluntarios.app_id, app.nome as origem, categoria, municipio, telefone, mensagem, voluntarios.metadados, voluntarios.created_by, voluntarios.created_at from voluntarios join app on app_id = app.uid  ORDER BY created_at DESC LIMIT $1 OFFSET $2"
-        )
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&state.db)
-        .await
-        .map_err(internal_error)?;
+        query.build_query_as::<Voluntario>()
+            .fetch_all(&state.db)
+            .await?;
 
     Ok(Json(PaginatedResponse {
         data: rows,
         total: total.0,
-        page: pagination.page.unwrap_or(1),
+        page: pagination.page(),
         per_page: limit,
     }))
 }
 
+// voluntario da atribuicao em aberto mais recente de cada solicitacao
+const VOLUNTARIO_ATUAL_JOIN: &str = r#"
+    left join lateral (
+        select json_build_object('uid', v.uid, 'nome', v.nome, 'atribuicao_uid', a.uid, 'atribuicao_status', a.status) as voluntario_atual
+        from atribuicoes a join voluntarios v on v.uid = a.voluntario_id
+        where a.solicitacao_id = solicitacoes.uid and a.status in ('PENDENTE', 'ACEITA', 'EM_ANDAMENTO')
+        order by a.created_at desc
+        limit 1
+    ) atual on true
+"#;
+
+#[utoipa::path(
+    get, path = "/solicitacoes", tag = "solicitacoes",
+    params(Pagination, Filter),
+    responses((status = 200, body = PaginatedResponse<Solicitacao>))
+)]
 pub async fn list_solicitacoes(
     State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
     Query(pagination): Query<Pagination>,
+    Query(filter): Query<Filter>,
 ) -> Result<Json<PaginatedResponse<Solicitacao>>, AppError> {
 
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
+
     let (limit, offset) = pagination.limit_offset();
 
     // total
+    let mut count = QueryBuilder::new("SELECT COUNT(*) FROM solicitacoes");
+    filter.push_where(&mut count, &filter::SOLICITACOES, app_id);
+
     let total: (i64,) =
-        sqlx::query_as("SELECT COUNT(*) FROM solicitacoes")
+        count.build_query_as()
             .fetch_one(&state.db)
             .await?;
 
     // dados
+    let mut query = QueryBuilder::new(
+        r#"
+        select solicitacoes.uid, solicitacoes.nome, solicitacoes.app_id, app.nome as origem, categoria, municipio, telefone, mensagem, solicitacoes.votos, solicitacoes.status, solicitacoes.duplicada_de, solicitacoes.metadados, solicitacoes.created_by, solicitacoes.created_at, atual.voluntario_atual from solicitacoes join app on app_id = app.uid
+        "#
+    );
+    query.push(VOLUNTARIO_ATUAL_JOIN);
+    filter.push_where(&mut query, &filter::SOLICITACOES, app_id);
+    query.push(" ORDER BY solicitacoes.created_at DESC LIMIT ").push_bind(limit)
+        .push(" OFFSET ").push_bind(offset);
+
     let rows =
-        sqlx::query_as::<_, Solicitacao>(
-            r#"
-            select solicitacoes.nome, solicitacoes.app_id, app.nome as origem, categoria, municipio, telefone, mensagem, solicitacoes.votos, solicitacoes.status, solicitacoes.metadados, solicitacoes.created_by, solicitacoes.created_at from solicitacoes join app on app_id = app.uid  ORDER BY created_at DESC LIMIT $1 OFFSET $2
-            "#
-        )
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&state.db)
-        .await?;
+        query.build_query_as::<Solicitacao>()
+            .fetch_all(&state.db)
+            .await?;
 
     Ok(Json(PaginatedResponse {
         data: rows,
         total: total.0,
-        page: pagination.page.unwrap_or(1),
+        page: pagination.page(),
         per_page: limit,
     }))
 }
 
+#[utoipa::path(
+    get, path = "/denuncias", tag = "denuncias",
+    params(Pagination, Filter),
+    responses((status = 200, body = PaginatedResponse<Denuncia>))
+)]
 pub async fn list_denuncias(
     State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
     Query(pagination): Query<Pagination>,
+    Query(filter): Query<Filter>,
 ) -> Result<Json<PaginatedResponse<Denuncia>>, AppError> {
 
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
+
     let (limit, offset) = pagination.limit_offset();
 
     // total
+    let mut count = QueryBuilder::new("SELECT COUNT(*) FROM denuncias");
+    filter.push_where(&mut count, &filter::DENUNCIAS, app_id);
+
     let total: (i64,) =
-        sqlx::query_as("SELECT COUNT(*) FROM denuncias")
+        count.build_query_as()
             .fetch_one(&state.db)
             .await?;
 
     // dados
+    let mut query = QueryBuilder::new(
+        r#"
+        select denuncias.uid, denuncias.tipo, denuncias.app_id, app.nome as origem, memorando, denuncias.url, denuncias.chave_pix, denuncias.evidencias, denuncias.metadados, denuncias.status, denuncias.moderado_em, denuncias.created_by, denuncias.created_at from denuncias join app on app_id = app.uid
+        "#
+    );
+    filter.push_where(&mut query, &filter::DENUNCIAS, app_id);
+    query.push(" ORDER BY denuncias.created_at DESC LIMIT ").push_bind(limit)
+        .push(" OFFSET ").push_bind(offset);
+
     let rows =
-        sqlx::query_as::<_, Denuncia>(
-            r#"
-            select denuncias.uid, denuncias.tipo, denuncias.app_id, app.nome as origem, memorando, denuncias.url, denuncias.chave_pix, denuncias.evidencias, denuncias.metadados, denuncias.created_by, denuncias.created_at from denuncias join app on app_id = app.uid  ORDER BY created_at DESC LIMIT $1 OFFSET $2
-            "#
-        )
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&state.db)
-        .await?;
+        query.build_query_as::<Denuncia>()
+            .fetch_all(&state.db)
+            .await?;
 
     Ok(Json(PaginatedResponse {
         data: rows,
         total: total.0,
-        page: pagination.page.unwrap_or(1),
+        page: pagination.page(),
         per_page: limit,
     }))
 }
 
+#[utoipa::path(
+    get, path = "/pontos.geojson", tag = "pontos",
+    params(Filter),
+    responses((status = 200, body = FeatureCollection, content_type = "application/geo+json"))
+)]
+pub async fn pontos_geojson(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Query(filter): Query<Filter>,
+) -> Result<FeatureCollection, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
+    ensure_filtro_permitido(&current, &filter)?;
+
+    let mut query = QueryBuilder::new(
+        "select pontos.uid, pontos.nome, pontos.app_id, app.nome as origem, categoria, pontos.tipo, municipio, endereco, telefone, bairro, horarios, pontos.responsavel, pix, cnpj, itens, gps, latitude, longitude, pontos.metadados, pontos.votos, pontos.bloqueado_em, pontos.denuncia_bloqueio, pontos.created_at, pontos.created_by from pontos join app on app_id = app.uid"
+    );
+    filter.push_where(&mut query, &filter::PONTOS, app_id);
+    query.push(" AND latitude IS NOT NULL AND longitude IS NOT NULL ORDER BY pontos.created_at DESC");
+
+    let rows =
+        query.build_query_as::<Ponto>()
+            .fetch_all(&state.db)
+            .await?;
+
+    let items = rows.into_iter().filter_map(|ponto| {
+        let coordenadas = Coordenadas::new(ponto.latitude?, ponto.longitude?).ok()?;
+        Some((coordenadas, ponto))
+    });
+
+    FeatureCollection::from_items(items).map_err(|_| AppError::Internal)
+}
+
+#[utoipa::path(
+    get, path = "/solicitacoes.geojson", tag = "solicitacoes",
+    params(Filter),
+    responses((status = 200, body = FeatureCollection, content_type = "application/geo+json"))
+)]
+pub async fn solicitacoes_geojson(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Query(filter): Query<Filter>,
+) -> Result<FeatureCollection, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
+
+    let mut query = QueryBuilder::new(
+        r#"
+        select solicitacoes.uid, solicitacoes.nome, solicitacoes.app_id, app.nome as origem, categoria, municipio, telefone, mensagem, solicitacoes.votos, solicitacoes.status, solicitacoes.duplicada_de, solicitacoes.metadados, solicitacoes.created_by, solicitacoes.created_at, atual.voluntario_atual from solicitacoes join app on app_id = app.uid
+        "#
+    );
+    query.push(VOLUNTARIO_ATUAL_JOIN);
+    filter.push_where(&mut query, &filter::SOLICITACOES, app_id);
+    query.push(" AND solicitacoes.metadados IS NOT NULL ORDER BY solicitacoes.created_at DESC");
+
+    let rows =
+        query.build_query_as::<Solicitacao>()
+            .fetch_all(&state.db)
+            .await?;
+
+    // somente as que trazem coordenadas no metadados
+    let items = rows.into_iter().filter_map(|solicitacao| {
+        let coordenadas = coordenadas_de_metadados(solicitacao.metadados.as_ref()?)?;
+        Some((coordenadas, solicitacao))
+    });
+
+    FeatureCollection::from_items(items).map_err(|_| AppError::Internal)
+}
+
+// CTE "proximos": pontos com coordenadas e a distancia (haversine) ate a origem,
+// pre-filtrados pela faixa de latitude (1 grau ~ 111 km)
+fn push_pontos_proximos(
+    qb: &mut QueryBuilder<'_, sqlx::Postgres>,
+    origem: Coordenadas,
+    raio_km: f64,
+    filter: &Filter,
+    app_id: &str,
+) {
+    qb.push("WITH proximos AS (SELECT pontos.*, 2 * 6371 * asin(sqrt(power(sin(radians(latitude - ")
+        .push_bind(origem.latitude)
+        .push(") / 2), 2) + cos(radians(").push_bind(origem.latitude)
+        .push(")) * cos(radians(latitude)) * power(sin(radians(longitude - ").push_bind(origem.longitude)
+        .push(") / 2), 2))) AS distancia_km FROM pontos");
+    filter.push_where(qb, &filter::PONTOS, app_id);
+    qb.push(" AND latitude IS NOT NULL AND longitude IS NOT NULL AND latitude BETWEEN ")
+        .push_bind(origem.latitude - raio_km / 111.0)
+        .push(" AND ").push_bind(origem.latitude + raio_km / 111.0)
+        .push(") ");
+}
+
+#[utoipa::path(
+    get, path = "/pontos/nearby", tag = "pontos",
+    params(NearbyQuery, Pagination, Filter),
+    responses((status = 200, body = PaginatedResponse<PontoProximo>))
+)]
+pub async fn list_pontos_nearby(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Query(nearby): Query<NearbyQuery>,
+    Query(pagination): Query<Pagination>,
+    Query(filter): Query<Filter>,
+) -> Result<Json<PaginatedResponse<PontoProximo>>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
+    ensure_filtro_permitido(&current, &filter)?;
+
+    let origem = Coordenadas::new(nearby.lat, nearby.lon)
+        .map_err(AppError::Validation)?;
+    let raio_km = nearby.radius_km.unwrap_or(10.0);
+    if !(raio_km > 0.0 && raio_km <= 500.0) {
+        return Err(AppError::Validation("radius_km deve estar entre 0 e 500".into()));
+    }
+
+    let (limit, offset) = pagination.limit_offset();
+
+    let mut count = QueryBuilder::new("");
+    push_pontos_proximos(&mut count, origem, raio_km, &filter, app_id);
+    count.push("SELECT COUNT(*) FROM proximos WHERE distancia_km <= ").push_bind(raio_km);
+
+    let total: (i64,) =
+        count.build_query_as()
+            .fetch_one(&state.db)
+            .await?;
+
+    let mut query = QueryBuilder::new("");
+    push_pontos_proximos(&mut query, origem, raio_km, &filter, app_id);
+    query.push(
+        r#"
+        select proximos.uid, proximos.nome, proximos.app_id, app.nome as origem, categoria, proximos.tipo, municipio, endereco, telefone, bairro, horarios, proximos.responsavel, pix, cnpj, itens, gps, latitude, longitude, proximos.metadados, proximos.votos, proximos.bloqueado_em, proximos.denuncia_bloqueio, proximos.created_at, proximos.created_by, distancia_km
+        from proximos join app on app_id = app.uid
+        where distancia_km <= "#
+    )
+    .push_bind(raio_km)
+    .push(" ORDER BY distancia_km LIMIT ").push_bind(limit)
+    .push(" OFFSET ").push_bind(offset);
+
+    let rows =
+        query.build_query_as::<PontoProximo>()
+            .fetch_all(&state.db)
+            .await?;
+
+    Ok(Json(PaginatedResponse {
+        data: rows,
+        total: total.0,
+        page: pagination.page(),
+        per_page: limit,
+    }))
+}
+
+#[utoipa::path(
+    post, path = "/pontos", tag = "pontos",
+    request_body = NewPonto,
+    responses((status = 200, body = Ponto))
+)]
 pub async fn create_ponto(
     State(state): State<AppState>,
     Extension(current): Extension<CurrentUser>,
     Json(payload): Json<NewPonto>,
 ) -> Result<Json<Ponto>, AppError> {
 
-    let app_id = ensure_app(&current)?;
+    let app_id = ensure_papel(&current, Papel::Voluntario)?;
+
+    let mut erros = Erros::default();
+    payload.validar(&mut erros);
+    let coordenadas = erros.opcional("gps", &payload.gps, parse_gps);
+    let pix = erros.opcional("pix", &payload.pix, normalizar_chave_pix).map(|(_, chave)| chave);
+    let cnpj = erros.opcional("cnpj", &payload.cnpj, normalizar_cnpj);
+    erros.into_result()?;
+
+    denuncias::ensure_chaves_liberadas(&state.db, state.bloqueio, app_id, &[pix.as_deref(), cnpj.as_deref()]).await?;
 
     let record =
         sqlx::query_as::<_, Ponto>(
@@ -284,16 +797,19 @@ pub async fn create_ponto(
             INSERT INTO pontos
             (app_id, nome, categoria, tipo, municipio, endereco,
              telefone, bairro, horarios, responsavel,
-             pix, cnpj, itens, gps, metadados)
+             pix, cnpj, itens, gps, metadados,
+             latitude, longitude)
             VALUES
             ($1, $2,
              COALESCE($3, 'Ponto de Coleta'),
              COALESCE($4, 'Voluntario'),
              COALESCE($5, 'Juiz de Fora'),
              $6, $7, $8, $9, $10,
-             $11, $12, $13, $14, $15)
+             $11, $12, $13, $14, $15,
+             $16, $17)
             RETURNING * ) 
             SELECT 
+                inserted.uid,
                 inserted.nome,
                 inserted.app_id,
                 app.nome AS origem,
@@ -309,7 +825,12 @@ pub async fn create_ponto(
                 inserted.cnpj,
                 inserted.itens,
                 inserted.gps,
+                inserted.latitude,
+                inserted.longitude,
                 inserted.metadados,
+                inserted.votos,
+                inserted.bloqueado_em,
+                inserted.denuncia_bloqueio,
                 inserted.created_at,
                 inserted.created_by
             FROM inserted
@@ -325,40 +846,672 @@ pub async fn create_ponto(
         .bind(&payload.bairro)
         .bind(&payload.horarios)
         .bind(&payload.responsavel)
-        .bind(&payload.pix)
-        .bind(&payload.cnpj)
+        .bind(&pix)
+        .bind(&cnpj)
         .bind(&payload.itens)
-        .bind(&payload.gps)
+        .bind(coordenadas.map(|c| c.to_gps()))
         .bind(&payload.metadados)
+        .bind(coordenadas.map(|c| c.latitude))
+        .bind(coordenadas.map(|c| c.longitude))
         .fetch_one(&state.db)
         .await?;
 
     Ok(Json(record))
 }
 
+#[utoipa::path(
+    get, path = "/pontos/{uid}", tag = "pontos",
+    params(("uid" = String, Path)),
+    responses((status = 200, body = Ponto))
+)]
+pub async fn get_ponto(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<Json<Ponto>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
+
+    let record = fetch_ponto(&state.db, &uid, app_id).await?;
+
+    Ok(Json(record))
+}
+
+pub async fn fetch_ponto<'e>(db: impl PgExecutor<'e>, uid: &str, app_id: &str) -> Result<Ponto, AppError> {
+    sqlx::query_as::<_, Ponto>(
+        r#"
+        select pontos.uid, pontos.nome, pontos.app_id, app.nome as origem, categoria, pontos.tipo, municipio, endereco, telefone, bairro, horarios, pontos.responsavel, pix, cnpj, itens, gps, latitude, longitude, pontos.metadados, pontos.votos, pontos.bloqueado_em, pontos.denuncia_bloqueio, pontos.created_at, pontos.created_by
+        from pontos join app on app_id = app.uid
+        where pontos.uid = $1 and pontos.app_id = $2
+        "#
+    )
+    .bind(uid)
+    .bind(app_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+pub async fn fetch_solicitacao<'e>(db: impl PgExecutor<'e>, uid: &str, app_id: &str) -> Result<Solicitacao, AppError> {
+    let sql = format!(
+        r#"
+        select solicitacoes.uid, solicitacoes.nome, solicitacoes.app_id, app.nome as origem, categoria, municipio, telefone, mensagem, solicitacoes.votos, solicitacoes.status, solicitacoes.duplicada_de, solicitacoes.metadados, solicitacoes.created_by, solicitacoes.created_at, atual.voluntario_atual
+        from solicitacoes join app on app_id = app.uid
+        {VOLUNTARIO_ATUAL_JOIN}
+        where solicitacoes.uid = $1 and solicitacoes.app_id = $2
+        "#
+    );
+    sqlx::query_as::<_, Solicitacao>(&sql)
+    .bind(uid)
+    .bind(app_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or(AppError::NotFound)
+}
+
+// Votos da comunidade confirmando que um ponto ou pedido e real e ativo.
+// A chave primaria da tabela de votos garante um voto por usuario e o
+// contador da tabela principal acompanha cada voto registrado ou retirado.
+struct Votacao {
+    tabela: &'static str,
+    votos: &'static str,
+    coluna: &'static str,
+}
+
+const VOTOS_PONTOS: Votacao = Votacao { tabela: "pontos", votos: "votos_pontos", coluna: "ponto_id" };
+const VOTOS_SOLICITACOES: Votacao = Votacao { tabela: "solicitacoes", votos: "votos_solicitacoes", coluna: "solicitacao_id" };
+
+async fn registrar_voto<'a>(
+    state: &AppState,
+    current: &'a CurrentUser,
+    votacao: &Votacao,
+    uid: &str,
+    votar: bool,
+) -> Result<&'a str, AppError> {
+
+    // voto e de pessoa: chaves de app nao votam
+    if current.kind != UserKind::User {
+        return Err(AppError::Forbidden);
+    }
+    let app_id = ensure_papel(current, Papel::Visualizador)?;
+
+    let mut tx = state.db.begin().await?;
+
+    let existe = format!("SELECT 1 FROM {} WHERE uid = $1 AND app_id = $2", votacao.tabela);
+    sqlx::query_scalar::<_, i32>(&existe)
+        .bind(uid)
+        .bind(app_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let sql = if votar {
+        format!("INSERT INTO {} (app_id, {}, user_id) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING", votacao.votos, votacao.coluna)
+    } else {
+        format!("DELETE FROM {} WHERE app_id = $1 AND {} = $2 AND user_id = $3", votacao.votos, votacao.coluna)
+    };
+    let res = sqlx::query(&sql)
+        .bind(app_id)
+        .bind(uid)
+        .bind(&current.user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if res.rows_affected() == 0 {
+        return Err(if votar {
+            AppError::Conflict("voto ja registrado".into())
+        } else {
+            AppError::NotFound
+        });
+    }
+
+    let contador = format!("UPDATE {} SET votos = votos + $1 WHERE uid = $2", votacao.tabela);
+    sqlx::query(&contador)
+        .bind(if votar { 1 } else { -1 })
+        .bind(uid)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(app_id)
+}
+
+#[utoipa::path(
+    post, path = "/pontos/{uid}/votos", tag = "pontos",
+    params(("uid" = String, Path)),
+    responses((status = 200, body = Ponto))
+)]
+pub async fn votar_ponto(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<Json<Ponto>, AppError> {
+    let app_id = registrar_voto(&state, &current, &VOTOS_PONTOS, &uid, true).await?;
+    Ok(Json(fetch_ponto(&state.db, &uid, app_id).await?))
+}
+
+#[utoipa::path(
+    delete, path = "/pontos/{uid}/votos", tag = "pontos",
+    params(("uid" = String, Path)),
+    responses((status = 200, body = Ponto))
+)]
+pub async fn retirar_voto_ponto(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<Json<Ponto>, AppError> {
+    let app_id = registrar_voto(&state, &current, &VOTOS_PONTOS, &uid, false).await?;
+    Ok(Json(fetch_ponto(&state.db, &uid, app_id).await?))
+}
+
+#[utoipa::path(
+    post, path = "/solicitacoes/{uid}/votos", tag = "solicitacoes",
+    params(("uid" = String, Path)),
+    responses((status = 200, body = Solicitacao))
+)]
+pub async fn votar_solicitacao(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<Json<Solicitacao>, AppError> {
+    let app_id = registrar_voto(&state, &current, &VOTOS_SOLICITACOES, &uid, true).await?;
+    Ok(Json(fetch_solicitacao(&state.db, &uid, app_id).await?))
+}
+
+#[utoipa::path(
+    delete, path = "/solicitacoes/{uid}/votos", tag = "solicitacoes",
+    params(("uid" = String, Path)),
+    responses((status = 200, body = Solicitacao))
+)]
+pub async fn retirar_voto_solicitacao(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<Json<Solicitacao>, AppError> {
+    let app_id = registrar_voto(&state, &current, &VOTOS_SOLICITACOES, &uid, false).await?;
+    Ok(Json(fetch_solicitacao(&state.db, &uid, app_id).await?))
+}
+
+#[utoipa::path(
+    patch, path = "/pontos/{uid}", tag = "pontos",
+    params(("uid" = String, Path)),
+    request_body = UpdatePonto,
+    responses((status = 200, body = Ponto))
+)]
+pub async fn update_ponto(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+    Json(payload): Json<UpdatePonto>,
+) -> Result<Json<Ponto>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Voluntario)?;
+
+    let mut erros = Erros::default();
+    payload.validar(&mut erros);
+    let coordenadas = erros.opcional("gps", &payload.gps, parse_gps);
+    let pix = erros.opcional("pix", &payload.pix, normalizar_chave_pix).map(|(_, chave)| chave);
+    let cnpj = erros.opcional("cnpj", &payload.cnpj, normalizar_cnpj);
+    erros.into_result()?;
+
+    denuncias::ensure_chaves_liberadas(&state.db, state.bloqueio, app_id, &[pix.as_deref(), cnpj.as_deref()]).await?;
+
+    let mut tx = state.db.begin().await?;
+
+    let mut record =
+        sqlx::query_as::<_, Ponto>(
+            r#"
+            WITH updated AS (
+            UPDATE pontos SET
+                nome = COALESCE($3, nome),
+                categoria = COALESCE($4, categoria),
+                tipo = COALESCE($5, tipo),
+                municipio = COALESCE($6, municipio),
+                endereco = COALESCE($7, endereco),
+                telefone = COALESCE($8, telefone),
+                bairro = COALESCE($9, bairro),
+                horarios = COALESCE($10, horarios),
+                responsavel = COALESCE($11, responsavel),
+                pix = COALESCE($12, pix),
+                cnpj = COALESCE($13, cnpj),
+                itens = COALESCE($14, itens),
+                gps = COALESCE($15, gps),
+                metadados = COALESCE($16::json, metadados),
+                latitude = COALESCE($17, latitude),
+                longitude = COALESCE($18, longitude)
+            WHERE uid = $1 AND app_id = $2
+            RETURNING * )
+            SELECT
+                updated.uid,
+                updated.nome,
+                updated.app_id,
+                app.nome AS origem,
+                updated.categoria,
+                updated.tipo,
+                updated.municipio,
+                updated.endereco,
+                updated.telefone,
+                updated.bairro,
+                updated.horarios,
+                updated.responsavel,
+                updated.pix,
+                updated.cnpj,
+                updated.itens,
+                updated.gps,
+                updated.latitude,
+                updated.longitude,
+                updated.metadados,
+                updated.votos,
+                updated.bloqueado_em,
+                updated.denuncia_bloqueio,
+                updated.created_at,
+                updated.created_by
+            FROM updated
+            JOIN app ON updated.app_id = app.uid; "#
+        )
+        .bind(&uid)
+        .bind(app_id)
+        .bind(&payload.nome)
+        .bind(&payload.categoria)
+        .bind(&payload.tipo)
+        .bind(&payload.municipio)
+        .bind(&payload.endereco)
+        .bind(&payload.telefone)
+        .bind(&payload.bairro)
+        .bind(&payload.horarios)
+        .bind(&payload.responsavel)
+        .bind(&pix)
+        .bind(&cnpj)
+        .bind(&payload.itens)
+        .bind(coordenadas.map(|c| c.to_gps()))
+        .bind(&payload.metadados)
+        .bind(coordenadas.map(|c| c.latitude))
+        .bind(coordenadas.map(|c| c.longitude))
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    // chave trocada pode tirar o ponto da quarentena
+    if pix.is_some() || cnpj.is_some() {
+        denuncias::reavaliar_bloqueios(&mut tx, state.bloqueio, Some(&uid)).await?;
+        record = fetch_ponto(&mut *tx, &uid, app_id).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(Json(record))
+}
+
+#[utoipa::path(
+    delete, path = "/pontos/{uid}", tag = "pontos",
+    params(("uid" = String, Path)),
+    responses((status = 204))
+)]
+pub async fn delete_ponto(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<StatusCode, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Coordenador)?;
+
+    let res = sqlx::query("DELETE FROM pontos WHERE uid = $1 AND app_id = $2")
+        .bind(&uid)
+        .bind(app_id)
+        .execute(&state.db)
+        .await?;
+
+    if res.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// nomes de itens sao guardados em maiusculas para agrupar "agua" e "Agua"
+fn normalizar_item(item: &str) -> Result<String, AppError> {
+    let item = item.trim().to_uppercase();
+    if item.is_empty() {
+        return Err(AppError::Validation("item nao pode ser vazio".into()));
+    }
+    Ok(item)
+}
+
+async fn ensure_ponto(
+    conn: &mut sqlx::PgConnection,
+    uid: &str,
+    app_id: &str,
+) -> Result<(), AppError> {
+    sqlx::query_scalar::<_, i32>("SELECT 1 FROM pontos WHERE uid = $1 AND app_id = $2")
+        .bind(uid)
+        .bind(app_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(())
+}
+
+#[utoipa::path(
+    get, path = "/pontos/{uid}/itens", tag = "pontos",
+    params(("uid" = String, Path)),
+    responses((status = 200, body = Vec<ItemPonto>))
+)]
+pub async fn list_itens_ponto(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<Json<Vec<ItemPonto>>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
+
+    let mut conn = state.db.acquire().await?;
+    ensure_ponto(&mut conn, &uid, app_id).await?;
+
+    let rows =
+        sqlx::query_as::<_, ItemPonto>(
+            r#"
+            select ponto_id, item, aceitando, recusando, estoque, estoque_minimo, app_id, updated_at
+            from itens_pontos
+            where ponto_id = $1
+            order by item
+            "#
+        )
+        .bind(&uid)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    Ok(Json(rows))
+}
+
+// Define aceitando/recusando/estoque de um item do ponto, criando o item se preciso
+#[utoipa::path(
+    put, path = "/pontos/{uid}/itens/{item}", tag = "pontos",
+    params(("uid" = String, Path), ("item" = String, Path)),
+    request_body = UpdateItemPonto,
+    responses((status = 200, body = ItemPonto))
+)]
+pub async fn update_item_ponto(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path((uid, item)): Path<(String, String)>,
+    Json(payload): Json<UpdateItemPonto>,
+) -> Result<Json<ItemPonto>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Voluntario)?;
+    let item = normalizar_item(&item)?;
+
+    if payload.estoque.is_some_and(|e| e < 0) || payload.estoque_minimo.is_some_and(|e| e < 0) {
+        return Err(AppError::Validation("estoque nao pode ser negativo".into()));
+    }
+    if payload.aceitando == Some(true) && payload.recusando == Some(true) {
+        return Err(AppError::Validation("item nao pode ser aceito e recusado ao mesmo tempo".into()));
+    }
+
+    let mut tx = state.db.begin().await?;
+    ensure_ponto(&mut tx, &uid, app_id).await?;
+
+    // aceitar desmarca recusar e vice-versa, para o item nao ficar nos dois estados
+    let record =
+        sqlx::query_as::<_, ItemPonto>(
+            r#"
+            INSERT INTO itens_pontos (ponto_id, app_id, item, aceitando, recusando, estoque, estoque_minimo)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (ponto_id, item) DO UPDATE SET
+                aceitando = COALESCE($4, CASE WHEN $5 THEN false END, itens_pontos.aceitando),
+                recusando = COALESCE($5, CASE WHEN $4 THEN false END, itens_pontos.recusando),
+                estoque = COALESCE($6, itens_pontos.estoque),
+                estoque_minimo = COALESCE($7, itens_pontos.estoque_minimo),
+                updated_at = now()
+            RETURNING ponto_id, item, aceitando, recusando, estoque, estoque_minimo, app_id, updated_at
+            "#
+        )
+        .bind(&uid)
+        .bind(app_id)
+        .bind(&item)
+        .bind(payload.aceitando)
+        .bind(payload.recusando)
+        .bind(payload.estoque)
+        .bind(payload.estoque_minimo)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(record))
+}
+
+// Soma (doacao recebida) ou subtrai (distribuicao) do estoque atual do item
+#[utoipa::path(
+    post, path = "/pontos/{uid}/itens/{item}/ajuste", tag = "pontos",
+    params(("uid" = String, Path), ("item" = String, Path)),
+    request_body = AjusteEstoque,
+    responses((status = 200, body = ItemPonto))
+)]
+pub async fn ajustar_estoque_item(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path((uid, item)): Path<(String, String)>,
+    Json(payload): Json<AjusteEstoque>,
+) -> Result<Json<ItemPonto>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Voluntario)?;
+    let item = normalizar_item(&item)?;
+
+    let mut tx = state.db.begin().await?;
+    ensure_ponto(&mut tx, &uid, app_id).await?;
+
+    // trava o item para que ajustes simultaneos nao se percam
+    let atual: Option<Option<i32>> =
+        sqlx::query_scalar("SELECT estoque FROM itens_pontos WHERE ponto_id = $1 AND item = $2 FOR UPDATE")
+            .bind(&uid)
+            .bind(&item)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+    let novo = atual.flatten().unwrap_or(0) as i64 + payload.quantidade as i64;
+    if novo < 0 {
+        return Err(AppError::Conflict(format!(
+            "estoque insuficiente: {} disponivel",
+            atual.flatten().unwrap_or(0)
+        )));
+    }
+    let novo = i32::try_from(novo)
+        .map_err(|_| AppError::Validation("quantidade muito grande".into()))?;
+
+    let record =
+        sqlx::query_as::<_, ItemPonto>(
+            r#"
+            INSERT INTO itens_pontos (ponto_id, app_id, item, estoque)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (ponto_id, item) DO UPDATE SET estoque = $4, updated_at = now()
+            RETURNING ponto_id, item, aceitando, recusando, estoque, estoque_minimo, app_id, updated_at
+            "#
+        )
+        .bind(&uid)
+        .bind(app_id)
+        .bind(&item)
+        .bind(novo)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(record))
+}
+
+#[utoipa::path(
+    delete, path = "/pontos/{uid}/itens/{item}", tag = "pontos",
+    params(("uid" = String, Path), ("item" = String, Path)),
+    responses((status = 204))
+)]
+pub async fn delete_item_ponto(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path((uid, item)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Voluntario)?;
+    let item = normalizar_item(&item)?;
+
+    let res = sqlx::query("DELETE FROM itens_pontos WHERE ponto_id = $1 AND item = $2 AND app_id = $3")
+        .bind(&uid)
+        .bind(&item)
+        .bind(app_id)
+        .execute(&state.db)
+        .await?;
+
+    if res.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Quadro "o que falta onde": agrega por municipio e bairro os itens aceitos,
+// recusados e com estoque abaixo do minimo em todos os pontos do escopo.
+// Itens do campo livre pontos.itens (ex: "AGUA, ROUPAS") contam como aceitos
+// quando o ponto nao tem o mesmo item cadastrado em itens_pontos.
+#[utoipa::path(
+    get, path = "/necessidades", tag = "necessidades",
+    params(Filter),
+    responses((status = 200, body = Vec<NecessidadesBairro>))
+)]
+pub async fn list_necessidades(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Query(filter): Query<Filter>,
+) -> Result<Json<Vec<NecessidadesBairro>>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
+    ensure_filtro_permitido(&current, &filter)?;
+
+    let mut query = QueryBuilder::new(
+        "WITH escopo AS (SELECT pontos.uid, pontos.municipio, pontos.bairro, pontos.itens FROM pontos"
+    );
+    filter.push_where(&mut query, &filter::PONTOS, app_id);
+    query.push(
+        r#"
+        ),
+        cadastrados AS (
+            SELECT escopo.municipio, escopo.bairro, i.item, escopo.uid,
+                   COALESCE(i.aceitando, false) AS aceitando,
+                   COALESCE(i.recusando, false) AS recusando,
+                   (i.estoque_minimo IS NOT NULL AND COALESCE(i.estoque, 0) < i.estoque_minimo) AS estoque_baixo
+            FROM itens_pontos i JOIN escopo ON escopo.uid = i.ponto_id
+        ),
+        texto_livre AS (
+            SELECT DISTINCT escopo.municipio, escopo.bairro, upper(trim(t.item)) AS item, escopo.uid,
+                   true AS aceitando, false AS recusando, false AS estoque_baixo
+            FROM escopo, regexp_split_to_table(escopo.itens, '[,;/|
+]+') AS t(item)
+            WHERE trim(t.item) <> ''
+              AND NOT EXISTS (
+                  SELECT 1 FROM itens_pontos i
+                  WHERE i.ponto_id = escopo.uid AND i.item = upper(trim(t.item))
+              )
+        ),
+        todos AS (
+            SELECT * FROM cadastrados
+            UNION ALL
+            SELECT * FROM texto_livre
+        )
+        SELECT municipio, bairro, item,
+               count(*) FILTER (WHERE aceitando) AS pontos_aceitando,
+               count(*) FILTER (WHERE recusando) AS pontos_recusando,
+               count(*) FILTER (WHERE estoque_baixo) AS pontos_estoque_baixo
+        FROM todos
+        GROUP BY municipio, bairro, item
+        ORDER BY municipio, bairro, pontos_estoque_baixo DESC, pontos_aceitando DESC, item
+        "#
+    );
+
+    let linhas =
+        query.build_query_as::<NecessidadeItem>()
+            .fetch_all(&state.db)
+            .await?;
+
+    // as linhas chegam ordenadas por municipio/bairro; agrupa as consecutivas
+    let mut quadro: Vec<NecessidadesBairro> = Vec::new();
+    for linha in linhas {
+        let mesmo_bairro = quadro
+            .last()
+            .is_some_and(|b| b.municipio == linha.municipio && b.bairro == linha.bairro);
+        if !mesmo_bairro {
+            quadro.push(NecessidadesBairro {
+                municipio: linha.municipio.clone(),
+                bairro: linha.bairro.clone(),
+                estoque_baixo: Vec::new(),
+                aceitando: Vec::new(),
+                recusando: Vec::new(),
+            });
+        }
+        let bairro = quadro.last_mut().expect("bairro inserido acima");
+
+        if linha.pontos_estoque_baixo > 0 {
+            bairro.estoque_baixo.push(ItemContagem { item: linha.item.clone(), pontos: linha.pontos_estoque_baixo });
+        }
+        if linha.pontos_aceitando > 0 {
+            bairro.aceitando.push(ItemContagem { item: linha.item.clone(), pontos: linha.pontos_aceitando });
+        }
+        if linha.pontos_recusando > 0 {
+            bairro.recusando.push(ItemContagem { item: linha.item, pontos: linha.pontos_recusando });
+        }
+    }
+
+    Ok(Json(quadro))
+}
+
+#[utoipa::path(
+    post, path = "/voluntarios", tag = "voluntarios",
+    request_body = NewVoluntario,
+    responses((status = 200, body = Voluntario))
+)]
 pub async fn create_voluntario(
     State(state): State<AppState>,
     Extension(current): Extension<CurrentUser>,
     Json(payload): Json<NewVoluntario>,
 ) -> Result<Json<Voluntario>, AppError> {
 
-    let app_id = ensure_app(&current)?;
+    let app_id = ensure_papel(&current, Papel::Voluntario)?;
+    payload.validado()?;
+
+    // quem se cadastra fica ligado ao proprio usuario; ligar outro usuario cabe ao coordenador
+    let proprio = (current.kind == UserKind::User).then_some(current.user_id.as_str());
+    let user_id = match payload.user_id.as_deref() {
+        Some(outro) if Some(outro) != proprio => {
+            ensure_papel(&current, Papel::Coordenador)?;
+            let do_app: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1 AND app_id = $2)")
+                .bind(outro)
+                .bind(app_id)
+                .fetch_one(&state.db)
+                .await?;
+            if !do_app {
+                return Err(AppError::campo("user_id", "usuario nao encontrado neste app"));
+            }
+            Some(outro)
+        }
+        Some(outro) => Some(outro),
+        None if current.papel < Some(Papel::Coordenador) => proprio,
+        None => None,
+    };
 
     let record =
         sqlx::query_as::<_, Voluntario>(
             r#"
             WITH inserted AS (
             INSERT INTO voluntarios
-            (app_id, nome, telefone, municipio, categoria, mensagem, metadados)
+            (app_id, nome, telefone, municipio, categoria, mensagem, metadados, user_id)
             VALUES
             ($1, $2, $3,
              COALESCE($4, 'Juiz de Fora'),
-             $5, $6, $7)
+             $5, $6, $7, $8)
             RETURNING * )
             select 
                 inserted.uid, 
                 inserted.nome, 
                 inserted.app_id, 
+                inserted.user_id, 
                 app.nome as origem, 
                 inserted.categoria, 
                 inserted.municipio, 
@@ -378,19 +1531,31 @@ pub async fn create_voluntario(
         .bind(&payload.categoria)
         .bind(&payload.mensagem)
         .bind(&payload.metadados)
+        .bind(user_id)
         .fetch_one(&state.db)
         .await?;
 
     Ok(Json(record))
 }
 
+#[utoipa::path(
+    post, path = "/solicitacoes", tag = "solicitacoes",
+    request_body = NewSolicitacao,
+    responses((status = 200, body = Solicitacao))
+)]
 pub async fn create_solicitacao(
     State(state): State<AppState>,
     Extension(current): Extension<CurrentUser>,
     Json(payload): Json<NewSolicitacao>,
 ) -> Result<Json<Solicitacao>, AppError> {
 
-    let app_id = ensure_app(&current)?;
+    let app_id = ensure_papel(&current, Papel::Voluntario)?;
+    payload.validado()?;
+
+    let telefone_normalizado = telefone_nacional(&payload.telefone);
+    let municipio = payload.municipio.as_deref().unwrap_or("Juiz de Fora");
+    let duplicada_de =
+        duplicatas::buscar_original(&state.db, &telefone_normalizado, municipio, &payload.nome).await?;
 
     let record =
         sqlx::query_as::<_, Solicitacao>(
@@ -398,11 +1563,9 @@ pub async fn create_solicitacao(
             WITH inserted AS (
             INSERT INTO solicitacoes
             (app_id, nome, telefone, municipio, categoria,
-             mensagem, metadados)
+             mensagem, metadados, telefone_normalizado, duplicada_de)
             VALUES
-            ($1, $2, $3,
-             COALESCE($4, 'Juiz de Fora'),
-             $5, $6, $7)
+            ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             RETURNING *)
             select 
                 inserted.uid, 
@@ -412,6 +1575,7 @@ pub async fn create_solicitacao(
                 inserted.categoria, 
                 inserted.status,
                 inserted.votos,
+                inserted.duplicada_de,
                 inserted.municipio, 
                 inserted.telefone, 
                 inserted.mensagem, 
@@ -424,23 +1588,160 @@ pub async fn create_solicitacao(
         .bind(app_id)
         .bind(&payload.nome)
         .bind(&payload.telefone)
-        .bind(&payload.municipio)
+        .bind(municipio)
         .bind(&payload.categoria)
         .bind(&payload.mensagem)
         .bind(&payload.metadados)
+        .bind(&telefone_normalizado)
+        .bind(&duplicada_de)
         .fetch_one(&state.db)
         .await?;
 
     Ok(Json(record))
 }
 
+#[utoipa::path(
+    post, path = "/solicitacoes/{uid}/status", tag = "solicitacoes",
+    params(("uid" = String, Path)),
+    request_body = UpdateStatusSolicitacao,
+    responses((status = 200, body = Solicitacao))
+)]
+pub async fn update_status_solicitacao(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+    Json(payload): Json<UpdateStatusSolicitacao>,
+) -> Result<Json<Solicitacao>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Coordenador)?;
+    payload.validado()?;
+
+    let mut tx = state.db.begin().await?;
+
+    alterar_status_solicitacao(&mut tx, &current, app_id, &uid, payload.status, payload.observacao.as_deref()).await?;
+
+    let record = fetch_solicitacao(&mut *tx, &uid, app_id).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(record))
+}
+
+// Aplica a transicao de status validando o fluxo e registrando o historico.
+// Devolve o status anterior.
+pub async fn alterar_status_solicitacao(
+    conn: &mut PgConnection,
+    current: &CurrentUser,
+    app_id: &str,
+    uid: &str,
+    novo: StatusSolicitacao,
+    observacao: Option<&str>,
+) -> Result<StatusSolicitacao, AppError> {
+
+    // trava a linha para evitar duas transicoes concorrentes
+    let atual: String =
+        sqlx::query_scalar("SELECT status FROM solicitacoes WHERE uid = $1 AND app_id = $2 FOR UPDATE")
+            .bind(uid)
+            .bind(app_id)
+            .fetch_optional(&mut *conn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+    let atual: StatusSolicitacao = atual
+        .parse()
+        .map_err(AppError::Conflict)?;
+
+    if !atual.pode_ir_para(novo) {
+        return Err(AppError::Conflict(format!(
+            "transicao invalida: {} -> {}",
+            atual, novo
+        )));
+    }
+
+    sqlx::query("UPDATE solicitacoes SET status = $1 WHERE uid = $2")
+        .bind(novo.as_str())
+        .bind(uid)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO solicitacoes_historico
+        (solicitacao_id, app_id, status_anterior, status_novo,
+         observacao, alterado_por, alterado_por_tipo)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#
+    )
+    .bind(uid)
+    .bind(app_id)
+    .bind(atual.as_str())
+    .bind(novo.as_str())
+    .bind(observacao)
+    .bind(&current.user_id)
+    .bind(current.kind.to_string())
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(atual)
+}
+
+#[utoipa::path(
+    get, path = "/solicitacoes/{uid}/historico", tag = "solicitacoes",
+    params(("uid" = String, Path)),
+    responses((status = 200, body = Vec<HistoricoSolicitacao>))
+)]
+pub async fn list_historico_solicitacao(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+) -> Result<Json<Vec<HistoricoSolicitacao>>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
+
+    let exists: Option<i32> =
+        sqlx::query_scalar("SELECT 1 FROM solicitacoes WHERE uid = $1 AND app_id = $2")
+            .bind(&uid)
+            .bind(app_id)
+            .fetch_optional(&state.db)
+            .await?;
+
+    if exists.is_none() {
+        return Err(AppError::NotFound);
+    }
+
+    let rows =
+        sqlx::query_as::<_, HistoricoSolicitacao>(
+            r#"
+            select uid, solicitacao_id, status_anterior, status_novo, observacao, alterado_por, alterado_por_tipo, created_at
+            from solicitacoes_historico
+            where solicitacao_id = $1
+            order by created_at
+            "#
+        )
+        .bind(&uid)
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(rows))
+}
+
+#[utoipa::path(
+    post, path = "/denuncias", tag = "denuncias",
+    request_body = NewDenuncia,
+    responses((status = 200, body = Denuncia))
+)]
 pub async fn create_denuncia(
     State(state): State<AppState>,
     Extension(current): Extension<CurrentUser>,
     Json(payload): Json<NewDenuncia>,
 ) -> Result<Json<Denuncia>, AppError> {
 
-    let app_id = ensure_app(&current)?;
+    let app_id = ensure_papel(&current, Papel::Visualizador)?;
+
+    let mut erros = Erros::default();
+    payload.validar(&mut erros);
+    let chave_pix = erros.opcional("chave_pix", &payload.chave_pix, normalizar_chave_pix).map(|(_, chave)| chave);
+    erros.into_result()?;
 
     let record =
         sqlx::query_as::<_, Denuncia>(
@@ -467,6 +1768,8 @@ pub async fn create_denuncia(
                 inserted.chave_pix, 
                 inserted.evidencias, 
                 inserted.metadados, 
+                inserted.status,
+                inserted.moderado_em,
                 inserted.created_by, 
                 inserted.created_at 
             from inserted 
@@ -477,10 +1780,11 @@ pub async fn create_denuncia(
         .bind(&payload.tipo)
         .bind(&payload.memorando)
         .bind(&payload.url)
-        .bind(&payload.chave_pix)
+        .bind(&chave_pix)
         .bind(&payload.evidencias)
         .bind(&payload.metadados)
-        .bind(&payload.created_by)
+        // autor e o usuario que chama, nunca o corpo da requisicao; chaves de app ficam sem autor
+        .bind((current.kind == UserKind::User).then_some(current.user_id.as_str()))
         .fetch_one(&state.db)
         .await?;
 
@@ -1,51 +1,195 @@
-use jsonwebtoken::{encode, decode, Header, Validation, EncodingKey, DecodingKey};
+use jsonwebtoken::{encode, decode, Algorithm, Header, Validation, EncodingKey, DecodingKey};
 use serde::{Serialize, Deserialize};
-// use uuid::Uuid;
+use uuid::Uuid;
 use chrono::{Utc, Duration};
+use std::env;
+use thiserror::Error;
+use tracing::warn;
 
-const SECRET: &[u8] = b"23aacf19-d4ed-5fc4-a1ad-db0dbc9d882c-8962a11f-7fff-5b5a-92cb-cc9275dbb447";
+use crate::roles::Papel;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
     pub exp: usize,
+    pub iat: usize,
+    // identificador do token, usado na revogacao (logout)
+    pub jti: String,
+    // sessao aberta no login, compartilhada pelos tokens renovados
+    pub sid: String,
+    // app escolhido no login e o papel do usuario nele
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub papel: Option<Papel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
 }
 
-// use thiserror::Error;
-// #[derive(Debug, Error)]
-// pub enum JwtError {
-//     #[error("token creation failed")]
-//     Creation(#[from] jsonwebtoken::errors::Error),
+#[derive(Debug, Error)]
+pub enum JwtConfigError {
+    #[error("JWT_SECRET must be set when APP_ENV=production")]
+    MissingSecret,
 
-//     #[error("token validation failed")]
-//     Validation(#[from] jsonwebtoken::errors::Error),
-// }
+    #[error("unsupported JWT_ALGORITHM: {0} (use HS256, HS384, HS512, RS256 or EdDSA)")]
+    UnsupportedAlgorithm(String),
+
+    #[error("{0} must be set for JWT_ALGORITHM={1}")]
+    MissingKeyPath(&'static str, String),
+
+    #[error("could not read key file {0}: {1}")]
+    KeyFile(String, std::io::Error),
+
+    #[error("invalid key material: {0}")]
+    InvalidKey(#[from] jsonwebtoken::errors::Error),
+
+    #[error("JWT_EXPIRATION_MINUTES must be a positive number")]
+    InvalidExpiration,
+
+    #[error("REFRESH_TOKEN_DAYS must be a positive number")]
+    InvalidRefreshExpiration,
+}
 
 pub type JwtResult<T> = Result<T, jsonwebtoken::errors::Error>;
 
-pub fn generate(user_id: &String) -> JwtResult<String> {
-    let exp = Utc::now()
-        .checked_add_signed(Duration::hours(24))
+// Chaves e parametros de assinatura carregados do ambiente na inicializacao
+//
+// APP_ENV                 production | development (padrao)
+// JWT_ALGORITHM           HS256 (padrao), HS384, HS512, RS256, EdDSA
+// JWT_SECRET              segredo para HS*; obrigatorio em producao
+// JWT_PRIVATE_KEY_PATH    chave privada PEM para RS256/EdDSA
+// JWT_PUBLIC_KEY_PATH     chave publica PEM para RS256/EdDSA
+// JWT_EXPIRATION_MINUTES  validade do access token (padrao 15)
+// REFRESH_TOKEN_DAYS      validade do refresh token (padrao 30)
+// JWT_ISSUER, JWT_AUDIENCE  claims iss/aud opcionais
+pub struct JwtConfig {
+    algorithm: Algorithm,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    pub expiration: Duration,
+    pub refresh_expiration: Duration,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+impl JwtConfig {
+    pub fn from_env() -> Result<Self, JwtConfigError> {
+        let production = env::var("APP_ENV")
+            .map(|v| v.eq_ignore_ascii_case("production"))
+            .unwrap_or(false);
+
+        let algorithm_name = env::var("JWT_ALGORITHM").unwrap_or_else(|_| "HS256".into());
+        let algorithm = match algorithm_name.as_str() {
+            "HS256" => Algorithm::HS256,
+            "HS384" => Algorithm::HS384,
+            "HS512" => Algorithm::HS512,
+            "RS256" => Algorithm::RS256,
+            "EdDSA" => Algorithm::EdDSA,
+            _ => return Err(JwtConfigError::UnsupportedAlgorithm(algorithm_name)),
+        };
+
+        let (encoding, decoding) = match algorithm {
+            Algorithm::RS256 | Algorithm::EdDSA => {
+                let private_pem = read_key("JWT_PRIVATE_KEY_PATH", &algorithm_name)?;
+                let public_pem = read_key("JWT_PUBLIC_KEY_PATH", &algorithm_name)?;
+                if algorithm == Algorithm::RS256 {
+                    (EncodingKey::from_rsa_pem(&private_pem)?, DecodingKey::from_rsa_pem(&public_pem)?)
+                } else {
+                    (EncodingKey::from_ed_pem(&private_pem)?, DecodingKey::from_ed_pem(&public_pem)?)
+                }
+            }
+            _ => {
+                let secret = match env::var("JWT_SECRET").ok().filter(|s| !s.is_empty()) {
+                    Some(secret) => secret.into_bytes(),
+                    None if production => return Err(JwtConfigError::MissingSecret),
+                    None => {
+                        // desenvolvimento: segredo aleatorio, tokens deixam de valer ao reiniciar
+                        warn!("JWT_SECRET not set, using a random secret (tokens will not survive a restart)");
+                        rand::random::<[u8; 32]>().to_vec()
+                    }
+                };
+                (EncodingKey::from_secret(&secret), DecodingKey::from_secret(&secret))
+            }
+        };
+
+        let minutes: i64 = match env::var("JWT_EXPIRATION_MINUTES") {
+            Ok(v) => v.parse().ok().filter(|m| *m > 0).ok_or(JwtConfigError::InvalidExpiration)?,
+            Err(_) => 15,
+        };
+
+        let refresh_days: i64 = match env::var("REFRESH_TOKEN_DAYS") {
+            Ok(v) => v.parse().ok().filter(|d| *d > 0).ok_or(JwtConfigError::InvalidRefreshExpiration)?,
+            Err(_) => 30,
+        };
+
+        Ok(JwtConfig {
+            algorithm,
+            encoding,
+            decoding,
+            expiration: Duration::minutes(minutes),
+            refresh_expiration: Duration::days(refresh_days),
+            issuer: env::var("JWT_ISSUER").ok().filter(|s| !s.is_empty()),
+            audience: env::var("JWT_AUDIENCE").ok().filter(|s| !s.is_empty()),
+        })
+    }
+}
+
+fn read_key(var: &'static str, algorithm: &str) -> Result<Vec<u8>, JwtConfigError> {
+    let path = env::var(var).map_err(|_| JwtConfigError::MissingKeyPath(var, algorithm.to_string()))?;
+    std::fs::read(&path).map_err(|e| JwtConfigError::KeyFile(path, e))
+}
+
+pub fn generate(
+    config: &JwtConfig,
+    user_id: &str,
+    session_id: &str,
+    app_id: Option<&str>,
+    papel: Option<Papel>,
+) -> JwtResult<(String, Claims)> {
+    let now = Utc::now();
+    let exp = now
+        .checked_add_signed(config.expiration)
         .expect("valid timestamp")
         .timestamp() as usize;
 
-    let claims = Claims { sub: user_id.clone(), exp };
+    let claims = Claims {
+        sub: user_id.to_string(),
+        exp,
+        iat: now.timestamp() as usize,
+        jti: Uuid::new_v4().to_string(),
+        sid: session_id.to_string(),
+        app: app_id.map(str::to_string),
+        papel,
+        iss: config.issuer.clone(),
+        aud: config.audience.clone(),
+    };
 
     let token = encode(
-        &Header::default(),
+        &Header::new(config.algorithm),
         &claims,
-        &EncodingKey::from_secret(SECRET),
-    )?;  
+        &config.encoding,
+    )?;
 
-    Ok(token)
+    Ok((token, claims))
 }
 
-pub fn validate(token: &str) -> JwtResult<Claims> {
+pub fn validate(config: &JwtConfig, token: &str) -> JwtResult<Claims> {
+    let mut validation = Validation::new(config.algorithm);
+    match &config.audience {
+        Some(audience) => validation.set_audience(&[audience]),
+        None => validation.validate_aud = false,
+    }
+    if let Some(issuer) = &config.issuer {
+        validation.set_issuer(&[issuer]);
+    }
+
     let data = decode::<Claims>(
         token,
-        &DecodingKey::from_secret(SECRET),
-        &Validation::default(),
+        &config.decoding,
+        &validation,
     )?;
 
-    Ok(data.claims) 
-}
\ No newline at end of file
+    Ok(data.claims)
+}
@@ -0,0 +1,28 @@
+// modulos compartilhados pelo binario (main.rs) e pelos testes de integracao (tests/)
+pub mod db;
+pub mod handlers;
+pub mod models;
+pub mod payloads;
+pub mod routes;
+pub mod middleware;
+pub mod pagination;
+pub mod filter;
+pub mod geo;
+pub mod geojson;
+
+pub mod jwt;
+pub mod auth;
+pub mod password;
+pub mod error;
+pub mod extract;
+pub mod openapi;
+pub mod workflow;
+pub mod api_keys;
+pub mod roles;
+pub mod matching;
+pub mod atribuicoes;
+pub mod duplicatas;
+pub mod denuncias;
+pub mod validacao;
+pub mod armazenamento;
+pub mod evidencias;
@@ -1,25 +1,16 @@
 use dotenvy::dotenv;  
 use std::env;  
 use std::net::SocketAddr;  
+use std::sync::Arc;
 use tokio::{net::TcpListener, signal};  
-use tracing_subscriber;
   
-mod db;  
-mod handlers;  
-mod models;  
-mod payloads;  
-mod routes;  
-mod middleware;
-mod pagination;
-
-mod jwt;
-mod auth;
-mod password; 
-mod error;
-  
-use db::connect;  
-use handlers::AppState;  
-use routes::create_router; 
+use emapi::api_keys::{self, ApiKeyCache, TipoChave};
+use emapi::armazenamento::Armazenamento;
+use emapi::db::connect;
+use emapi::denuncias::ConfigBloqueio;  
+use emapi::handlers::AppState;  
+use emapi::jwt::JwtConfig;
+use emapi::routes::create_router; 
   
 #[tokio::main]  
 async fn main() {  
@@ -30,10 +21,41 @@ tracing_subscriber::fmt()
     )
     .init();
   
+let jwt = JwtConfig::from_env().expect("invalid JWT configuration");
+let armazenamento = Armazenamento::from_env().expect("invalid evidence storage configuration");
+let bloqueio = ConfigBloqueio::from_env().expect("invalid blocklist configuration");
+
 let database_url = env::var("DATABASE_URL").expect("DATABASE_URL not set");  
 let pool = connect(&database_url).await;  
+
+// tarefas de administracao pela linha de comando
+//   emapi nova-chave <app_uid> [label]  gera uma chave secreta para o app
+//   emapi super-admin <email>           permite ao usuario cadastrar e gerenciar apps
+let args: Vec<String> = env::args().collect();
+match args.get(1).map(String::as_str) {
+    Some("nova-chave") => {
+        let app_id = args.get(2).expect("usage: emapi nova-chave <app_uid> [label]");
+        let label = args.get(3).map(String::as_str).unwrap_or("bootstrap");
+        let criada = api_keys::create_key(&pool, app_id, label, TipoChave::Secret)
+            .await
+            .expect("could not create API key");
+        println!("{}", criada.chave);
+        return;
+    }
+    Some("super-admin") => {
+        let email = args.get(2).expect("usage: emapi super-admin <email>");
+        let res = sqlx::query("UPDATE users SET super_admin = true WHERE email = $1")
+            .bind(email)
+            .execute(&pool)
+            .await
+            .expect("could not update user");
+        println!("{} user(s) promoted", res.rows_affected());
+        return;
+    }
+    _ => {}
+}
   
-let state = AppState { db: pool };  
+let state = AppState { db: pool, jwt: Arc::new(jwt), api_keys: ApiKeyCache::default(), armazenamento: Arc::new(armazenamento), bloqueio };  
 let app = create_router(state);  
   
 let port: u16 = env::var("PORTAUTH")  
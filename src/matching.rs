@@ -0,0 +1,140 @@
+use axum::extract::State;
+use axum::Extension;
+use serde::Serialize;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+
+use crate::error::AppError;
+use crate::extract::{Json, Path, Query};
+use crate::geo::{coordenadas_de_metadados, distancia_km};
+use crate::handlers::{ensure_papel, fetch_solicitacao, AppState};
+use crate::middleware::CurrentUser;
+use crate::models::{Solicitacao, Voluntario};
+use crate::payloads::CandidatosQuery;
+use crate::roles::Papel;
+
+// Ranking de voluntarios para uma solicitacao
+//
+// Pontuacao (maior e melhor):
+//   categoria igual a do pedido        +40  (voluntario sem categoria: +10)
+//   mesmo municipio                    +30
+//   distancia, quando ambos tem coordenadas no metadados
+//                                      ate +30, caindo a zero em 50 km
+//   cada atribuicao em aberto          -10
+const PESO_CATEGORIA: f64 = 40.0;
+const PESO_SEM_CATEGORIA: f64 = 10.0;
+const PESO_MUNICIPIO: f64 = 30.0;
+const PESO_DISTANCIA: f64 = 30.0;
+const ALCANCE_KM: f64 = 50.0;
+const PESO_CARGA: f64 = 10.0;
+
+#[derive(FromRow)]
+struct VoluntarioCarga {
+    #[sqlx(flatten)]
+    voluntario: Voluntario,
+    carga: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Candidato {
+    #[serde(flatten)]
+    pub voluntario: Voluntario,
+    pub pontuacao: f64,
+    pub mesma_categoria: bool,
+    pub mesmo_municipio: bool,
+    pub distancia_km: Option<f64>,
+    // atribuicoes em aberto (pendentes, aceitas ou em andamento)
+    pub atribuicoes_ativas: i64,
+}
+
+fn iguais(a: Option<&str>, b: Option<&str>) -> bool {
+    matches!((a, b), (Some(a), Some(b)) if a.trim().eq_ignore_ascii_case(b.trim()))
+}
+
+pub fn pontuar(solicitacao: &Solicitacao, voluntario: Voluntario, carga: i64) -> Candidato {
+    let mesma_categoria = iguais(voluntario.categoria.as_deref(), solicitacao.categoria.as_deref());
+    let mesmo_municipio = iguais(Some(&voluntario.municipio), Some(&solicitacao.municipio));
+
+    let distancia = match (
+        solicitacao.metadados.as_ref().and_then(coordenadas_de_metadados),
+        voluntario.metadados.as_ref().and_then(coordenadas_de_metadados),
+    ) {
+        (Some(origem), Some(destino)) => Some(distancia_km(origem, destino)),
+        _ => None,
+    };
+
+    let mut pontuacao = 0.0;
+    if mesma_categoria {
+        pontuacao += PESO_CATEGORIA;
+    } else if voluntario.categoria.as_deref().is_none_or(|c| c.trim().is_empty()) {
+        pontuacao += PESO_SEM_CATEGORIA;
+    }
+    if mesmo_municipio {
+        pontuacao += PESO_MUNICIPIO;
+    }
+    if let Some(km) = distancia {
+        pontuacao += PESO_DISTANCIA * (1.0 - km / ALCANCE_KM).max(0.0);
+    }
+    pontuacao -= PESO_CARGA * carga as f64;
+
+    Candidato {
+        voluntario,
+        pontuacao: (pontuacao * 100.0).round() / 100.0,
+        mesma_categoria,
+        mesmo_municipio,
+        distancia_km: distancia.map(|km| (km * 100.0).round() / 100.0),
+        atribuicoes_ativas: carga,
+    }
+}
+
+// Voluntarios do app ordenados pela adequacao ao pedido;
+// quem ja esta atribuido a este pedido fica de fora
+#[utoipa::path(
+    get, path = "/solicitacoes/{uid}/candidatos", tag = "solicitacoes",
+    params(("uid" = String, Path), CandidatosQuery),
+    responses((status = 200, body = Vec<Candidato>))
+)]
+pub async fn list_candidatos(
+    State(state): State<AppState>,
+    Extension(current): Extension<CurrentUser>,
+    Path(uid): Path<String>,
+    Query(query): Query<CandidatosQuery>,
+) -> Result<Json<Vec<Candidato>>, AppError> {
+
+    let app_id = ensure_papel(&current, Papel::Coordenador)?;
+    let limite = query.limite.unwrap_or(10).clamp(1, 100) as usize;
+
+    let solicitacao = fetch_solicitacao(&state.db, &uid, app_id).await?;
+
+    let voluntarios =
+        sqlx::query_as::<_, VoluntarioCarga>(
+            r#"
+            select voluntarios.uid, voluntarios.nome, voluntarios.app_id, voluntarios.user_id, categoria, municipio, telefone, mensagem, voluntarios.metadados, voluntarios.created_at,
+                   (select count(*) from atribuicoes a
+                    where a.voluntario_id = voluntarios.uid
+                      and a.status in ('PENDENTE', 'ACEITA', 'EM_ANDAMENTO')) as carga
+            from voluntarios
+            where voluntarios.app_id = $1
+              and not exists (
+                  select 1 from atribuicoes a
+                  where a.voluntario_id = voluntarios.uid
+                    and a.solicitacao_id = $2
+                    and a.status in ('PENDENTE', 'ACEITA', 'EM_ANDAMENTO')
+              )
+            "#
+        )
+        .bind(app_id)
+        .bind(&uid)
+        .fetch_all(&state.db)
+        .await?;
+
+    let mut candidatos: Vec<Candidato> = voluntarios
+        .into_iter()
+        .map(|v| pontuar(&solicitacao, v.voluntario, v.carga))
+        .collect();
+
+    candidatos.sort_by(|a, b| b.pontuacao.total_cmp(&a.pontuacao));
+    candidatos.truncate(limite);
+
+    Ok(Json(candidatos))
+}
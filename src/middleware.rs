@@ -1,17 +1,19 @@
 use axum::{
     extract::{State},
-    http::{Request, StatusCode},
+    http::{Method, Request},
     response::Response,
     middleware::Next, 
 };
  
 // use headers::{Authorization, authorization::Bearer};
 use axum::body::Body;
-use tracing::{error, info}; 
 // use uuid::Uuid;
 
+use crate::api_keys::{self, TipoChave};
+use crate::error::AppError;
 use crate::handlers::AppState; 
 use crate::jwt;
+use crate::roles::Papel;
 
 #[derive(Clone, PartialEq)]
 pub enum UserKind {
@@ -33,41 +35,50 @@ impl std::fmt::Display for UserKind {
 pub struct CurrentUser {
     pub user_id: String,
     pub kind: UserKind,
+    // app em nome do qual a requisicao e feita e o papel nele
+    pub app_id: Option<String>,
+    pub papel: Option<Papel>,
+    // gerencia apps (tenants); nao depende de papel em um app
+    pub super_admin: bool,
+    // chave publishable: somente leitura
+    pub read_only: bool,
 }
 
 pub async fn auth_middleware(
     State(state): State<AppState>,
     mut req: Request<Body>,
     next: Next,
-) -> Result<Response, StatusCode> {
+) -> Result<Response, AppError> {
 
     // 🔹 Copia para String imediatamente (encerra borrow)
-    let emergency_id = req
+    let api_key = req
         .headers()
-        .get("x-emergencial-id")
+        .get("x-emergencial-key")
         .and_then(|v| v.to_str().ok())
         .map(|s| s.to_string());
 
-    if let Some(app_id) = emergency_id {         
-        // info!("{}", &app_id);
-        let exists: Option<i32> =
-        sqlx::query_scalar("SELECT 1 FROM app WHERE uid = $1 LIMIT 1")
-            .bind(&app_id)
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e| {
-                error!("Database error: {:?}", e);
-                StatusCode::INTERNAL_SERVER_ERROR
-            })?;
-         
-        if exists.is_none() {
-            return Err(StatusCode::UNAUTHORIZED);
+    if let Some(chave) = api_key {
+        let autenticada = api_keys::authenticate(&state, &chave)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        // chave publishable nao escreve
+        let read_only = autenticada.tipo == TipoChave::Publishable;
+        if read_only && !matches!(*req.method(), Method::GET | Method::HEAD) {
+            return Err(AppError::Forbidden);
         }
 
         // 🔹 insere usuário autenticado
+        // chave secreta age como admin do proprio app
+        let papel = if read_only { Papel::Visualizador } else { Papel::Admin };
+
         req.extensions_mut().insert(CurrentUser {
-            user_id: app_id,
+            user_id: autenticada.app_id.clone(),
             kind: UserKind::App,
+            app_id: Some(autenticada.app_id),
+            papel: Some(papel),
+            super_admin: false,
+            read_only,
         });
 
         return Ok(next.run(req).await);
@@ -79,36 +90,52 @@ pub async fn auth_middleware(
         .get(axum::http::header::AUTHORIZATION)
         .and_then(|v| v.to_str().ok())
         .map(|s| s.to_string())
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+        .ok_or(AppError::Unauthorized)?;
 
     let token = auth_header
         .strip_prefix("Bearer ")
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+        .ok_or(AppError::Unauthorized)?;
 
-    let claims = jwt::validate(token)
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let claims = jwt::validate(&state.jwt, token)
+        .map_err(|_| AppError::Unauthorized)?;
 
     // valida UUID
     // let user_uuid = Uuid::parse_str(&claims.sub)
-    //     .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    //     .map_err(|_| AppError::Unauthorized)?;
 
-    let exists: (bool,) =
+    // usuario ainda existe, o token nao foi revogado (logout) e o app da sessao esta ativo
+    let row: Option<(bool, bool)> =
         sqlx::query_as(
-            "SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)"
+            r#"
+            SELECT super_admin, ($3::varchar IS NULL OR EXISTS(SELECT 1 FROM app WHERE uid = $3 AND ativo))
+            FROM users
+            WHERE id = $1 AND NOT EXISTS(SELECT 1 FROM tokens_revogados WHERE jti = $2)
+            "#
         )
         .bind(claims.sub.clone())
-        .fetch_one(&state.db)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .bind(&claims.jti)
+        .bind(&claims.app)
+        .fetch_optional(&state.db)
+        .await?;
 
-    if !exists.0 {
-        return Err(StatusCode::UNAUTHORIZED);
-    }
+    let (super_admin, app_ativo) = row.ok_or(AppError::Unauthorized)?;
+
+    // app desativado: a sessao continua valida, mas sem papel em nenhum app
+    let (app_id, papel) = if app_ativo {
+        (claims.app.clone(), claims.papel)
+    } else {
+        (None, None)
+    };
 
     req.extensions_mut().insert(CurrentUser {
         user_id: claims.sub.clone(),
         kind: UserKind::User,
+        app_id,
+        papel,
+        super_admin,
+        read_only: false,
     });
+    req.extensions_mut().insert(claims);
 
     Ok(next.run(req).await)
 }
\ No newline at end of file
@@ -2,8 +2,11 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;   
 use chrono::{DateTime, Utc};
 use serde_json::Value; 
+use utoipa::ToSchema;
+
+use crate::validacao::TipoChavePix;
   
-#[derive(Serialize, FromRow)]  
+#[derive(Serialize, FromRow, ToSchema)]
 pub struct User {  
 pub id: String,  
 pub nome: String,  
@@ -12,7 +15,7 @@ pub email: String,
 pub senha: String,
 }  
   
-#[derive(Deserialize)]  
+#[derive(Deserialize, ToSchema)]
 pub struct CreateUser {  
 pub nome: String,  
 pub senha: String,
@@ -20,24 +23,68 @@ pub app_id: Option<String>,
 pub email: String,  
 }  
   
-#[derive(Serialize)]  
+#[derive(Serialize, ToSchema)]
 pub struct PingResponse {  
 pub status: String,  
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct AppStruct {
+    pub uid: String,
     pub nome: Option<String>,
     pub responsavel: Option<String>,
     pub repositorio: Option<String>,
     pub tipo: String,
     pub url: String,
     pub metadados: Option<Value>,
+    pub ativo: bool,
+    // compartilha os dados na visao federada (somente apps publicos)
+    pub federado: bool,
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+// app recem cadastrado com a chave secreta inicial, exibida somente aqui
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AppCriado {
+    #[serde(flatten)]
+    pub app: AppStruct,
+    pub chave: String,
+}
+
+// metadados da chave de API; o segredo nunca e devolvido depois de criado
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct ApiKey {
+    pub uid: String,
+    pub label: String,
+    pub tipo: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing)]
+    pub app_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiKeyCriada {
+    #[serde(flatten)]
+    pub key: ApiKey,
+    // valor completo, exibido somente na criacao
+    pub chave: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct PapelUsuario {
+    pub user_id: String,
+    pub nome: String,
+    pub email: String,
+    pub papel: String,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct Ponto { 
+    pub uid: String,
     pub nome: String,
     pub origem: String,
     pub categoria: String,
@@ -54,10 +101,25 @@ pub struct Ponto {
     pub cnpj: Option<String>,
     pub itens: Option<String>,
     pub gps: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
     pub metadados: Option<Value>,
+    // confirmacoes da comunidade de que o ponto e real e esta ativo
+    pub votos: i32,
+    // quarentena: pix ou cnpj com denuncia de golpe confirmada
+    pub bloqueado_em: Option<DateTime<Utc>>,
+    pub denuncia_bloqueio: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct PontoProximo {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub ponto: Ponto,
+    pub distancia_km: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct VotoPonto {
     #[serde(skip_serializing)]
@@ -67,22 +129,53 @@ pub struct VotoPonto {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct ItemPonto {
+    pub ponto_id: String,
     pub item: String,
     pub aceitando: Option<bool>,
     pub recusando: Option<bool>,
     pub estoque: Option<i32>,
+    pub estoque_minimo: Option<i32>,
     #[serde(skip_serializing)]
     pub app_id: String,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+// linha agregada do quadro de necessidades: quantos pontos do bairro estao em cada situacao
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct NecessidadeItem {
+    pub municipio: String,
+    pub bairro: String,
+    pub item: String,
+    pub pontos_aceitando: i64,
+    pub pontos_recusando: i64,
+    pub pontos_estoque_baixo: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ItemContagem {
+    pub item: String,
+    pub pontos: i64,
+}
+
+// o que falta e o que sobra em cada bairro
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct NecessidadesBairro {
+    pub municipio: String,
+    pub bairro: String,
+    pub estoque_baixo: Vec<ItemContagem>,
+    pub aceitando: Vec<ItemContagem>,
+    pub recusando: Vec<ItemContagem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct Voluntario {
     pub uid: String,
     #[serde(skip_serializing)]
     pub app_id: String,
+    // usuario que responde pelas atribuicoes deste voluntario
+    pub user_id: Option<String>,
     pub nome: String,
     pub telefone: String,
     pub municipio: String,
@@ -92,8 +185,9 @@ pub struct Voluntario {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct Solicitacao {  
+    pub uid: String,
     pub nome: String,
     pub origem: String,
     pub telefone: String,
@@ -103,12 +197,59 @@ pub struct Solicitacao {
     pub mensagem: String,
     pub created_at: DateTime<Utc>,
     pub status: String,
-    pub votos: Option<i32>,
+    pub votos: i32,
+    // possivel duplicata de outra solicitacao (confirmada quando status = DUPLICADO)
+    pub duplicada_de: Option<String>,
+    #[serde(skip_serializing)]
+    pub app_id: String,
+    // voluntario da atribuicao em aberto mais recente
+    #[sqlx(default, json(nullable))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voluntario_atual: Option<VoluntarioAtual>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VoluntarioAtual {
+    pub uid: String,
+    pub nome: String,
+    pub atribuicao_uid: String,
+    pub atribuicao_status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct Atribuicao {
+    pub uid: String,
+    pub voluntario_id: String,
+    pub solicitacao_id: Option<String>,
+    pub ponto_id: Option<String>,
+    pub turno_inicio: Option<DateTime<Utc>>,
+    pub turno_fim: Option<DateTime<Utc>>,
+    pub status: String,
+    pub observacao: Option<String>,
+    pub aceita_em: Option<DateTime<Utc>>,
+    pub recusada_em: Option<DateTime<Utc>>,
+    pub iniciada_em: Option<DateTime<Utc>>,
+    pub concluida_em: Option<DateTime<Utc>>,
+    pub cancelada_em: Option<DateTime<Utc>>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
     #[serde(skip_serializing)]
     pub app_id: String,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct HistoricoSolicitacao {
+    pub uid: String,
+    pub solicitacao_id: String,
+    pub status_anterior: String,
+    pub status_novo: String,
+    pub observacao: Option<String>,
+    pub alterado_por: String,
+    pub alterado_por_tipo: String,
+    pub created_at: DateTime<Utc>,
+}
  
- #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+ #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct Denuncia {
     pub uid: String,
     pub tipo: String,
@@ -117,8 +258,54 @@ pub struct Denuncia {
     pub chave_pix: Option<String>,
     pub evidencias: Option<String>, 
     pub metadados: Option<Value>,
+    pub status: String,
+    pub moderado_em: Option<DateTime<Utc>>,
     pub created_by: Option<String>,
     pub created_at: DateTime<Utc>,
     #[serde(skip_serializing)]
     pub app_id: String,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct ModeracaoDenuncia {
+    pub uid: String,
+    pub denuncia_id: String,
+    pub status_anterior: String,
+    pub status_novo: String,
+    pub nota: Option<String>,
+    pub moderado_por: String,
+    pub moderado_por_tipo: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// valores confirmados como golpe, publicos para os apps de doacao
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Blocklist {
+    pub chaves_pix: Vec<String>,
+    pub urls: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PixStatus {
+    pub chave: String,
+    pub tipo: TipoChavePix,
+    // true quando ha denuncia confirmada para a chave
+    pub bloqueada: bool,
+    pub denuncias_confirmadas: i64,
+    pub apps_que_confirmaram: i64,
+    pub denuncias_em_analise: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct Evidencia {
+    pub uid: String,
+    pub denuncia_id: String,
+    pub nome_arquivo: String,
+    pub mime: String,
+    pub tamanho: i64,
+    pub sha256: String,
+    #[serde(skip_serializing)]
+    pub chave_armazenamento: String,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
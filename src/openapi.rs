@@ -0,0 +1,172 @@
+use axum::Router;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::{RefOr, Response, ResponseBuilder};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::error::ErrorResponse;
+use crate::handlers::AppState;
+use crate::models::{AppStruct, User};
+use crate::pagination::{PaginatedResponse, Pagination};
+use crate::{api_keys, atribuicoes, auth, denuncias, duplicatas, evidencias, handlers, matching};
+
+// Documento OpenAPI 3 gerado das anotacoes dos handlers e dos tipos de
+// models/payloads. Servido em /openapi.json e navegavel em /docs.
+// Toda rota nova em routes.rs precisa entrar em `paths` (tests/openapi.rs verifica).
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "HUB EMERGENCIA API",
+        description = "API white label para projetos de voluntarios em situacoes de emergencia"
+    ),
+    paths(
+        handlers::ping,
+        handlers::create_user,
+        create_user_autenticado,
+        auth::generate_token,
+        auth::refresh_token,
+        auth::logout,
+        handlers::list_users,
+        handlers::list_papeis,
+        handlers::update_papel,
+        handlers::delete_papel,
+        handlers::list_apps,
+        list_apps_publico,
+        handlers::create_app,
+        handlers::update_app,
+        handlers::disable_app,
+        handlers::enable_app,
+        api_keys::list_keys,
+        api_keys::create_key_handler,
+        api_keys::revoke_key,
+        api_keys::rotate_key,
+        handlers::list_pontos,
+        handlers::create_ponto,
+        handlers::list_pontos_nearby,
+        handlers::pontos_geojson,
+        handlers::get_ponto,
+        handlers::update_ponto,
+        handlers::delete_ponto,
+        handlers::votar_ponto,
+        handlers::retirar_voto_ponto,
+        handlers::list_itens_ponto,
+        handlers::update_item_ponto,
+        handlers::delete_item_ponto,
+        handlers::ajustar_estoque_item,
+        handlers::list_necessidades,
+        handlers::list_solicitacoes,
+        handlers::create_solicitacao,
+        handlers::solicitacoes_geojson,
+        handlers::votar_solicitacao,
+        handlers::retirar_voto_solicitacao,
+        handlers::update_status_solicitacao,
+        handlers::list_historico_solicitacao,
+        matching::list_candidatos,
+        duplicatas::mesclar_solicitacao,
+        atribuicoes::list_atribuicoes_solicitacao,
+        atribuicoes::create_atribuicao_solicitacao,
+        atribuicoes::list_atribuicoes_ponto,
+        atribuicoes::create_atribuicao_ponto,
+        atribuicoes::update_status_atribuicao,
+        handlers::list_voluntarios,
+        handlers::create_voluntario,
+        handlers::list_denuncias,
+        handlers::create_denuncia,
+        denuncias::update_status_denuncia,
+        denuncias::list_moderacao_denuncia,
+        denuncias::blocklist,
+        denuncias::pix_status,
+        evidencias::list_evidencias,
+        evidencias::upload_evidencias,
+        evidencias::download_evidencia,
+    ),
+    components(schemas(ErrorResponse)),
+    modifiers(&Seguranca, &RespostaDeErro),
+    security(("chave" = []), ("bearer" = [])),
+    tags(
+        (name = "auth", description = "cadastro, login e sessoes"),
+        (name = "users", description = "usuarios e papeis no app"),
+        (name = "apps", description = "apps (tenants) e chaves de API"),
+        (name = "pontos", description = "pontos de coleta e distribuicao"),
+        (name = "solicitacoes", description = "pedidos de ajuda"),
+        (name = "voluntarios"),
+        (name = "atribuicoes", description = "voluntarios designados para solicitacoes e turnos"),
+        (name = "necessidades", description = "o que falta e o que sobra por bairro"),
+        (name = "denuncias", description = "denuncias de golpes e lista de bloqueio"),
+    )
+)]
+pub struct ApiDoc;
+
+// rotas que reaproveitam um handler ja documentado em outro caminho
+
+#[allow(dead_code)]
+#[utoipa::path(
+    post, path = "/users", tag = "users",
+    request_body = crate::models::CreateUser,
+    responses((status = 200, body = User))
+)]
+fn create_user_autenticado() {}
+
+#[allow(dead_code)]
+#[utoipa::path(
+    get, path = "/apps/view", tag = "apps",
+    params(Pagination),
+    responses((status = 200, body = PaginatedResponse<AppStruct>)),
+    security(())
+)]
+fn list_apps_publico() {}
+
+// X-Emergencial-Key (apps) ou Authorization: Bearer (usuarios)
+struct Seguranca;
+
+impl Modify for Seguranca {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "chave",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Emergencial-Key"))),
+        );
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+    }
+}
+
+// todas as rotas respondem erros no mesmo formato (ver error.rs)
+struct RespostaDeErro;
+
+impl Modify for RespostaDeErro {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let erro: RefOr<Response> = ResponseBuilder::new()
+            .description("erro com code estavel (bad_request, unauthorized, forbidden, not_found, conflict, validation_failed, internal_error)")
+            .content(
+                "application/json",
+                utoipa::openapi::ContentBuilder::new()
+                    .schema(Some(utoipa::openapi::Ref::from_schema_name("ErrorResponse")))
+                    .build(),
+            )
+            .build()
+            .into();
+
+        for item in openapi.paths.paths.values_mut() {
+            for operacao in [
+                &mut item.get,
+                &mut item.post,
+                &mut item.put,
+                &mut item.patch,
+                &mut item.delete,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                operacao.responses.responses.entry("default".into()).or_insert_with(|| erro.clone());
+            }
+        }
+    }
+}
+
+// /openapi.json e a pagina do Swagger UI em /docs
+pub fn router() -> Router<AppState> {
+    SwaggerUi::new("/docs").url("/openapi.json", ApiDoc::openapi()).into()
+}
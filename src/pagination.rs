@@ -1,23 +1,31 @@
 use serde::{Serialize, Deserialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct Pagination {
     pub page: Option<i64>,
     pub per_page: Option<i64> 
 }
 
 impl Pagination {
+    // pagina efetiva: page=0 ou negativo vira a primeira
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
     pub fn limit_offset(&self) -> (i64, i64) {
         let per_page = self.per_page.unwrap_or(50).clamp(1, 100);
-        let page = self.page.unwrap_or(1).max(1);
+        let page = self.page();
 
-        let offset = (page - 1) * per_page;
+        // saturado: pagina enorme vira um offset alem do fim, nao um estouro
+        let offset = (page - 1).saturating_mul(per_page);
 
         (per_page, offset)
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PaginatedResponse<T> {
     pub data: Vec<T>,
     pub total: i64,
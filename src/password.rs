@@ -2,8 +2,6 @@ use argon2::{Argon2, PasswordHash, PasswordVerifier};
 use password_hash::SaltString; 
 use thiserror::Error;
 use argon2::PasswordHasher;
-use axum::http::StatusCode;
-use axum::response::{Response, IntoResponse};
 use rand::rngs::OsRng;
 
 #[derive(Debug, Error)]
@@ -12,15 +10,6 @@ pub enum PasswordError {
     HashError(#[from] argon2::password_hash::Error),
 }
 
-impl IntoResponse for PasswordError {
-    fn into_response(self) -> Response {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            self.to_string(),
-        ).into_response()
-    }
-}
-
 pub fn hash_password(password: &str) -> Result<String, PasswordError> {
     let salt = SaltString::generate(&mut OsRng);
 
@@ -40,4 +29,12 @@ pub fn verify_password(password: &str, password_hash: &str) -> bool {
     Argon2::default()
         .verify_password(password.as_bytes(), &parsed_hash)
         .is_ok()
-}
\ No newline at end of file
+}
+
+// segredo aleatorio (256 bits em hex) para refresh tokens e chaves de API
+pub fn random_secret() -> String {
+    rand::random::<[u8; 32]>()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
@@ -1,7 +1,15 @@
 use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
 use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Deserialize, Serialize)]
+use crate::api_keys::TipoChave;
+use crate::models::CreateUser;
+use crate::roles::Papel;
+use crate::validacao::{url_valida, Erros, Validar, MAX_CURTO, MAX_ENDERECO, MAX_NOME, MAX_OBSERVACAO, MAX_SENHA, MAX_TEXTO, MAX_URL, MIN_SENHA};
+use crate::workflow::{StatusAtribuicao, StatusDenuncia, StatusSolicitacao};
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct NewApp {
     pub email: String,
     pub nome: Option<String>,
@@ -10,9 +18,33 @@ pub struct NewApp {
     pub tipo: Option<String>, // opcional para permitir default
     pub url: String,
     pub metadados: Option<Value>,
+    pub federado: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpdateApp {
+    pub email: Option<String>,
+    pub nome: Option<String>,
+    pub responsavel: Option<String>,
+    pub repositorio: Option<String>,
+    pub tipo: Option<String>,
+    pub url: Option<String>,
+    pub metadados: Option<Value>,
+    pub federado: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct NewApiKey {
+    pub label: String,
+    pub tipo: TipoChave,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpdatePapel {
+    pub papel: Papel,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct NewPonto {
     pub nome: String,
     pub categoria: Option<String>,
@@ -31,7 +63,49 @@ pub struct NewPonto {
     pub created_by: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct NearbyQuery {
+    pub lat: f64,
+    pub lon: f64,
+    pub radius_km: Option<f64>,
+}
+
+// PATCH: apenas os campos presentes sao alterados
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpdatePonto {
+    pub nome: Option<String>,
+    pub categoria: Option<String>,
+    pub tipo: Option<String>,
+    pub municipio: Option<String>,
+    pub endereco: Option<String>,
+    pub telefone: Option<String>,
+    pub bairro: Option<String>,
+    pub horarios: Option<String>,
+    pub responsavel: Option<String>,
+    pub pix: Option<String>,
+    pub cnpj: Option<String>,
+    pub itens: Option<String>,
+    pub gps: Option<String>,
+    pub metadados: Option<Value>,
+}
+
+// campos ausentes mantem o valor atual do item
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpdateItemPonto {
+    pub aceitando: Option<bool>,
+    pub recusando: Option<bool>,
+    pub estoque: Option<i32>,
+    pub estoque_minimo: Option<i32>,
+}
+
+// entrada (positiva) ou saida (negativa) de estoque
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct AjusteEstoque {
+    pub quantidade: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct NewVoluntario {
     pub nome: String,
     pub telefone: String,
@@ -40,9 +114,11 @@ pub struct NewVoluntario {
     pub mensagem: String,
     pub metadados: Option<Value>,
     pub created_by: Option<String>,
+    // usuario do app ligado ao cadastro (coordenador); sem ele, quem se cadastra e o proprio usuario
+    pub user_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct NewSolicitacao {
     pub nome: String,
     pub telefone: String,
@@ -53,7 +129,50 @@ pub struct NewSolicitacao {
     pub created_by: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpdateStatusSolicitacao {
+    pub status: StatusSolicitacao,
+    pub observacao: Option<String>,
+}
+
+// sem original_uid usa o original apontado na deteccao automatica
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct MesclarSolicitacao {
+    pub original_uid: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct NewAtribuicao {
+    pub voluntario_uid: String,
+    pub observacao: Option<String>,
+    // turno, obrigatorio nas atribuicoes em pontos
+    pub turno_inicio: Option<DateTime<Utc>>,
+    pub turno_fim: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpdateStatusAtribuicao {
+    pub status: StatusAtribuicao,
+    pub observacao: Option<String>,
+    // ao concluir, marca tambem a solicitacao como ATENDIDO
+    #[serde(default)]
+    pub atender_solicitacao: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CandidatosQuery {
+    // quantos voluntarios devolver (padrao 10, maximo 100)
+    pub limite: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct UpdateStatusDenuncia {
+    pub status: StatusDenuncia,
+    pub nota: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct NewDenuncia {
     pub tipo: String,
     pub memorando: Option<String>,
@@ -61,5 +180,161 @@ pub struct NewDenuncia {
     pub chave_pix: Option<String>,
     pub evidencias: Option<String>, 
     pub metadados: Option<Value>,
-    pub created_by: Option<String>,
+}
+
+// Regras de validacao de cada payload (ver validacao::Validar)
+
+impl Validar for CreateUser {
+    fn validar(&self, erros: &mut Erros) {
+        erros.obrigatorio("nome", &self.nome, MAX_NOME);
+        erros.email("email", &self.email);
+        if self.senha.chars().count() < MIN_SENHA {
+            erros.campo("senha", format!("senha fraca: minimo de {MIN_SENHA} caracteres"));
+        }
+        erros.tamanho("senha", Some(&self.senha), MAX_SENHA);
+        erros.tamanho("app_id", self.app_id.as_deref(), MAX_CURTO);
+    }
+}
+
+impl Validar for NewApp {
+    fn validar(&self, erros: &mut Erros) {
+        erros.email("email", &self.email);
+        erros.tamanho("nome", self.nome.as_deref(), MAX_NOME);
+        erros.tamanho("responsavel", self.responsavel.as_deref(), MAX_NOME);
+        erros.tamanho("repositorio", self.repositorio.as_deref(), MAX_URL);
+        erros.url("url", &self.url);
+        erros.metadados("metadados", self.metadados.as_ref());
+    }
+}
+
+impl Validar for UpdateApp {
+    fn validar(&self, erros: &mut Erros) {
+        if let Some(email) = &self.email {
+            erros.email("email", email);
+        }
+        erros.tamanho("nome", self.nome.as_deref(), MAX_NOME);
+        erros.tamanho("responsavel", self.responsavel.as_deref(), MAX_NOME);
+        erros.tamanho("repositorio", self.repositorio.as_deref(), MAX_URL);
+        if let Some(url) = &self.url {
+            erros.url("url", url);
+        }
+        erros.metadados("metadados", self.metadados.as_ref());
+    }
+}
+
+impl Validar for NewApiKey {
+    fn validar(&self, erros: &mut Erros) {
+        erros.obrigatorio("label", &self.label, MAX_CURTO);
+    }
+}
+
+impl Validar for NewPonto {
+    fn validar(&self, erros: &mut Erros) {
+        erros.obrigatorio("nome", &self.nome, MAX_NOME);
+        erros.telefone("telefone", &self.telefone);
+        erros.obrigatorio("bairro", &self.bairro, MAX_CURTO);
+        erros.tamanho("categoria", self.categoria.as_deref(), MAX_CURTO);
+        erros.tamanho("tipo", self.tipo.as_deref(), MAX_CURTO);
+        erros.tamanho("municipio", self.municipio.as_deref(), MAX_CURTO);
+        erros.tamanho("endereco", self.endereco.as_deref(), MAX_ENDERECO);
+        erros.tamanho("horarios", self.horarios.as_deref(), MAX_ENDERECO);
+        erros.tamanho("responsavel", self.responsavel.as_deref(), MAX_NOME);
+        erros.tamanho("itens", self.itens.as_deref(), MAX_TEXTO);
+        erros.metadados("metadados", self.metadados.as_ref());
+        erros.tamanho("created_by", self.created_by.as_deref(), MAX_NOME);
+    }
+}
+
+impl Validar for UpdatePonto {
+    fn validar(&self, erros: &mut Erros) {
+        if let Some(nome) = &self.nome {
+            erros.obrigatorio("nome", nome, MAX_NOME);
+        }
+        if let Some(telefone) = &self.telefone {
+            erros.telefone("telefone", telefone);
+        }
+        if let Some(bairro) = &self.bairro {
+            erros.obrigatorio("bairro", bairro, MAX_CURTO);
+        }
+        erros.tamanho("categoria", self.categoria.as_deref(), MAX_CURTO);
+        erros.tamanho("tipo", self.tipo.as_deref(), MAX_CURTO);
+        erros.tamanho("municipio", self.municipio.as_deref(), MAX_CURTO);
+        erros.tamanho("endereco", self.endereco.as_deref(), MAX_ENDERECO);
+        erros.tamanho("horarios", self.horarios.as_deref(), MAX_ENDERECO);
+        erros.tamanho("responsavel", self.responsavel.as_deref(), MAX_NOME);
+        erros.tamanho("itens", self.itens.as_deref(), MAX_TEXTO);
+        erros.metadados("metadados", self.metadados.as_ref());
+    }
+}
+
+impl Validar for NewVoluntario {
+    fn validar(&self, erros: &mut Erros) {
+        erros.obrigatorio("nome", &self.nome, MAX_NOME);
+        erros.telefone("telefone", &self.telefone);
+        erros.tamanho("municipio", self.municipio.as_deref(), MAX_CURTO);
+        erros.tamanho("categoria", self.categoria.as_deref(), MAX_CURTO);
+        erros.obrigatorio("mensagem", &self.mensagem, MAX_TEXTO);
+        erros.metadados("metadados", self.metadados.as_ref());
+        erros.tamanho("created_by", self.created_by.as_deref(), MAX_NOME);
+        erros.tamanho("user_id", self.user_id.as_deref(), MAX_CURTO);
+    }
+}
+
+impl Validar for NewSolicitacao {
+    fn validar(&self, erros: &mut Erros) {
+        erros.obrigatorio("nome", &self.nome, MAX_NOME);
+        erros.telefone("telefone", &self.telefone);
+        erros.tamanho("municipio", self.municipio.as_deref(), MAX_CURTO);
+        erros.tamanho("categoria", self.categoria.as_deref(), MAX_CURTO);
+        erros.obrigatorio("mensagem", &self.mensagem, MAX_TEXTO);
+        erros.metadados("metadados", self.metadados.as_ref());
+        erros.tamanho("created_by", self.created_by.as_deref(), MAX_NOME);
+    }
+}
+
+impl Validar for UpdateStatusSolicitacao {
+    fn validar(&self, erros: &mut Erros) {
+        erros.tamanho("observacao", self.observacao.as_deref(), MAX_OBSERVACAO);
+    }
+}
+
+impl Validar for MesclarSolicitacao {
+    fn validar(&self, erros: &mut Erros) {
+        erros.tamanho("original_uid", self.original_uid.as_deref(), MAX_CURTO);
+    }
+}
+
+impl Validar for NewAtribuicao {
+    fn validar(&self, erros: &mut Erros) {
+        erros.obrigatorio("voluntario_uid", &self.voluntario_uid, MAX_CURTO);
+        erros.tamanho("observacao", self.observacao.as_deref(), MAX_OBSERVACAO);
+    }
+}
+
+impl Validar for UpdateStatusAtribuicao {
+    fn validar(&self, erros: &mut Erros) {
+        erros.tamanho("observacao", self.observacao.as_deref(), MAX_OBSERVACAO);
+    }
+}
+
+impl Validar for UpdateStatusDenuncia {
+    fn validar(&self, erros: &mut Erros) {
+        erros.tamanho("nota", self.nota.as_deref(), MAX_OBSERVACAO);
+    }
+}
+
+impl Validar for NewDenuncia {
+    fn validar(&self, erros: &mut Erros) {
+        erros.obrigatorio("tipo", &self.tipo, MAX_CURTO);
+        erros.tamanho("memorando", self.memorando.as_deref(), MAX_TEXTO);
+        erros.url("url", &self.url);
+        // uma ou mais urls separadas por virgula ou espaco
+        if let Some(evidencias) = self.evidencias.as_deref() {
+            let urls: Vec<&str> = evidencias.split([',', ' ', '\n']).filter(|u| !u.trim().is_empty()).collect();
+            if urls.iter().any(|u| !url_valida(u.trim())) || evidencias.len() > MAX_TEXTO {
+                erros.campo("evidencias", "use urls http:// ou https:// separadas por virgula");
+            }
+        }
+        erros.metadados("metadados", self.metadados.as_ref());
+    }
 }
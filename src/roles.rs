@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Papel de um usuario dentro de um app, do menor para o maior privilegio
+//
+// VISUALIZADOR  apenas consulta
+// VOLUNTARIO    cadastra pontos, voluntarios e solicitacoes
+// COORDENADOR   altera status de solicitacoes, remove pontos, ve usuarios
+// ADMIN         gerencia papeis e chaves de API do app
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Papel {
+    Visualizador,
+    Voluntario,
+    Coordenador,
+    Admin,
+}
+
+impl Papel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Papel::Visualizador => "VISUALIZADOR",
+            Papel::Voluntario => "VOLUNTARIO",
+            Papel::Coordenador => "COORDENADOR",
+            Papel::Admin => "ADMIN",
+        }
+    }
+}
+
+impl std::fmt::Display for Papel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for Papel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "VISUALIZADOR" => Ok(Papel::Visualizador),
+            "VOLUNTARIO" => Ok(Papel::Voluntario),
+            "COORDENADOR" => Ok(Papel::Coordenador),
+            "ADMIN" => Ok(Papel::Admin),
+            outro => Err(format!("papel desconhecido: {outro}")),
+        }
+    }
+}
@@ -1,32 +1,99 @@
-use axum::{routing::{get, post}, Router};  
+use axum::{extract::DefaultBodyLimit, handler::Handler, routing::MethodRouter, Router};  
 use crate::handlers::{ping, AppState};
 use crate::handlers::{list_users, list_apps, list_pontos, list_solicitacoes, list_voluntarios, list_denuncias};
 use crate::handlers::{create_user, create_ponto, create_solicitacao, create_voluntario, create_denuncia};
-use crate::auth::{generate_token};  
+use crate::handlers::{get_ponto, update_ponto, delete_ponto, list_pontos_nearby};
+use crate::handlers::{pontos_geojson, solicitacoes_geojson};
+use crate::handlers::{update_status_solicitacao, list_historico_solicitacao};
+use crate::handlers::{list_papeis, update_papel, delete_papel};
+use crate::handlers::{create_app, update_app, disable_app, enable_app};
+use crate::handlers::list_necessidades;
+use crate::matching::list_candidatos;
+use crate::duplicatas::mesclar_solicitacao;
+use crate::denuncias::{update_status_denuncia, list_moderacao_denuncia, blocklist, pix_status};
+use crate::evidencias::{upload_evidencias, list_evidencias, download_evidencia, TAMANHO_MAXIMO_UPLOAD};
+use crate::atribuicoes::{create_atribuicao_solicitacao, list_atribuicoes_solicitacao};
+use crate::atribuicoes::{create_atribuicao_ponto, list_atribuicoes_ponto, update_status_atribuicao};
+use crate::handlers::{votar_ponto, retirar_voto_ponto, votar_solicitacao, retirar_voto_solicitacao};
+use crate::handlers::{list_itens_ponto, update_item_ponto, ajustar_estoque_item, delete_item_ponto};
+use crate::auth::{generate_token, refresh_token, logout};  
+use crate::api_keys::{list_keys, create_key_handler, rotate_key, revoke_key};
 use axum::middleware::from_fn_with_state;
 use crate::middleware;
+use crate::openapi;
   
-pub fn create_router(state: AppState) -> Router {  
-    let public_routes = Router::new()
-        .route("/ping", get(ping))
-        .route("/apps/view", get(list_apps) )
-        .route("/register", post(create_user))
-        .route("/login", post(generate_token).get(generate_token));
+// Tabela de rotas: gera o Router e a lista (metodo, caminho) que o teste
+// do OpenAPI confere contra o /openapi.json
+macro_rules! rotas {
+    ($router:ident, $lista:ident; $($caminho:literal => $($metodo:ident($handler:expr)).+;)*) => {
+        pub const $lista: &[(&str, &str)] = &[$($((stringify!($metodo), $caminho),)+)*];
+
+        fn $router() -> Router<AppState> {
+            Router::new()$(.route($caminho, MethodRouter::new()$(.$metodo($handler))+))*
+        }
+    };
+}
+
+rotas! { rotas_publicas, ROTAS_PUBLICAS;
+    "/ping" => get(ping);
+    "/apps/view" => get(list_apps);
+    "/register" => post(create_user);
+    "/login" => post(generate_token).get(generate_token);
+    "/token/refresh" => post(refresh_token);
+    "/denuncias/blocklist" => get(blocklist);
+    "/pix/{chave}/status" => get(pix_status);
+}
 
-    // 🔐 Rotas privadas
-    let private_routes = Router::new()
-        .route("/users", get(list_users).post(create_user))
-        .route("/apps", get(list_apps) )
-        .route("/pontos", get(list_pontos).post(create_ponto))
-        .route("/solicitacoes", get(list_solicitacoes).post(create_solicitacao))
-        .route("/voluntarios", get(list_voluntarios).post(create_voluntario))
-        .route("/denuncias", get(list_denuncias).post(create_denuncia))
+// 🔐 Rotas privadas
+rotas! { rotas_privadas, ROTAS_PRIVADAS;
+    "/users" => get(list_users).post(create_user);
+    "/users/{id}/papel" => put(update_papel).delete(delete_papel);
+    "/papeis" => get(list_papeis);
+    "/logout" => post(logout);
+    "/apps" => get(list_apps).post(create_app);
+    "/apps/{uid}" => patch(update_app);
+    "/apps/{uid}/disable" => post(disable_app);
+    "/apps/{uid}/enable" => post(enable_app);
+    "/apps/keys" => get(list_keys).post(create_key_handler);
+    "/apps/keys/{uid}" => delete(revoke_key);
+    "/apps/keys/{uid}/rotate" => post(rotate_key);
+    "/pontos" => get(list_pontos).post(create_ponto);
+    "/pontos/nearby" => get(list_pontos_nearby);
+    "/pontos.geojson" => get(pontos_geojson);
+    "/pontos/{uid}" => get(get_ponto).patch(update_ponto).delete(delete_ponto);
+    "/pontos/{uid}/votos" => post(votar_ponto).delete(retirar_voto_ponto);
+    "/pontos/{uid}/itens" => get(list_itens_ponto);
+    "/pontos/{uid}/itens/{item}" => put(update_item_ponto).delete(delete_item_ponto);
+    "/pontos/{uid}/itens/{item}/ajuste" => post(ajustar_estoque_item);
+    "/necessidades" => get(list_necessidades);
+    "/solicitacoes" => get(list_solicitacoes).post(create_solicitacao);
+    "/solicitacoes.geojson" => get(solicitacoes_geojson);
+    "/solicitacoes/{uid}/candidatos" => get(list_candidatos);
+    "/solicitacoes/{uid}/mesclar" => post(mesclar_solicitacao);
+    "/solicitacoes/{uid}/atribuicoes" => get(list_atribuicoes_solicitacao).post(create_atribuicao_solicitacao);
+    "/pontos/{uid}/atribuicoes" => get(list_atribuicoes_ponto).post(create_atribuicao_ponto);
+    "/atribuicoes/{uid}/status" => post(update_status_atribuicao);
+    "/solicitacoes/{uid}/votos" => post(votar_solicitacao).delete(retirar_voto_solicitacao);
+    "/solicitacoes/{uid}/status" => post(update_status_solicitacao);
+    "/solicitacoes/{uid}/historico" => get(list_historico_solicitacao);
+    "/voluntarios" => get(list_voluntarios).post(create_voluntario);
+    "/denuncias" => get(list_denuncias).post(create_denuncia);
+    "/denuncias/{uid}/status" => post(update_status_denuncia);
+    "/denuncias/{uid}/moderacao" => get(list_moderacao_denuncia);
+    // so o upload aceita corpo maior que o padrao
+    "/denuncias/{uid}/evidencias" => get(list_evidencias).post(upload_evidencias.layer(DefaultBodyLimit::max(TAMANHO_MAXIMO_UPLOAD)));
+    "/denuncias/{uid}/evidencias/{evidencia}" => get(download_evidencia);
+}
+  
+pub fn create_router(state: AppState) -> Router {  
+    let private_routes = rotas_privadas()
         .route_layer(
             from_fn_with_state(state.clone(), middleware::auth_middleware)
         );
   
   Router::new()
-        .merge(public_routes)
+        .merge(rotas_publicas())
         .merge(private_routes)
+        .merge(openapi::router())
         .with_state(state)
 }
\ No newline at end of file
@@ -0,0 +1,337 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::error::{AppError, ErroCampo};
+
+// Limites dos campos de texto dos payloads (em caracteres)
+pub const MAX_NOME: usize = 120;
+pub const MAX_CURTO: usize = 120;
+pub const MAX_ENDERECO: usize = 255;
+pub const MAX_OBSERVACAO: usize = 1000;
+pub const MAX_TEXTO: usize = 2000;
+pub const MAX_URL: usize = 2048;
+pub const MAX_EMAIL: usize = 254;
+// metadados livres dos apps, medidos no json serializado (bytes)
+pub const MAX_METADADOS: usize = 16 * 1024;
+pub const MIN_SENHA: usize = 6;
+pub const MAX_SENHA: usize = 128;
+
+// Regras de um payload, checadas no handler antes de qualquer SQL.
+// Handlers que tambem normalizam campos (pix, cnpj, gps) passam o mesmo
+// Erros para devolver todos os problemas em um unico 422
+pub trait Validar {
+    fn validar(&self, erros: &mut Erros);
+
+    fn validado(&self) -> Result<(), AppError> {
+        let mut erros = Erros::default();
+        self.validar(&mut erros);
+        erros.into_result()
+    }
+}
+
+// Validacao e normalizacao de documentos e chaves pix
+//
+// Os valores sao gravados ja normalizados para que a mesma chave digitada
+// de formas diferentes (pontuacao, maiusculas, telefone sem +55) seja
+// reconhecida na lista de bloqueio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TipoChavePix {
+    Cpf,
+    Cnpj,
+    Email,
+    Telefone,
+    // chave aleatoria (EVP), um uuid gerado pelo banco
+    Aleatoria,
+}
+
+// Erros acumulados de um payload; vira um unico 422 com todos os campos
+#[derive(Debug, Default)]
+pub struct Erros(Vec<ErroCampo>);
+
+impl Erros {
+    pub fn campo(&mut self, campo: &str, mensagem: impl Into<String>) {
+        self.0.push(ErroCampo { campo: campo.to_string(), mensagem: mensagem.into() });
+    }
+
+    // valida um campo opcional guardando o erro, se houver
+    pub fn verificar<T>(&mut self, campo: &str, resultado: Result<T, String>) -> Option<T> {
+        match resultado {
+            Ok(valor) => Some(valor),
+            Err(mensagem) => {
+                self.campo(campo, mensagem);
+                None
+            }
+        }
+    }
+
+    // campo opcional do payload; vazio conta como ausente
+    pub fn opcional<T>(&mut self, campo: &str, valor: &Option<String>, validar: impl Fn(&str) -> Result<T, String>) -> Option<T> {
+        let valor = valor.as_deref().map(str::trim).filter(|v| !v.is_empty())?;
+        self.verificar(campo, validar(valor))
+    }
+
+    // texto obrigatorio: nao vazio e dentro do limite
+    pub fn obrigatorio(&mut self, campo: &str, valor: &str, max: usize) {
+        if valor.trim().is_empty() {
+            self.campo(campo, "campo obrigatorio");
+        } else {
+            self.tamanho(campo, Some(valor), max);
+        }
+    }
+
+    pub fn tamanho(&mut self, campo: &str, valor: Option<&str>, max: usize) {
+        if valor.is_some_and(|v| v.chars().count() > max) {
+            self.campo(campo, format!("maximo de {max} caracteres"));
+        }
+    }
+
+    pub fn email(&mut self, campo: &str, valor: &str) {
+        if valor.trim().is_empty() {
+            self.campo(campo, "campo obrigatorio");
+        } else if valor.len() > MAX_EMAIL || !email_valido(valor.trim()) {
+            self.campo(campo, "email invalido");
+        }
+    }
+
+    // telefone brasileiro com DDD; a pontuacao e livre
+    pub fn telefone(&mut self, campo: &str, valor: &str) {
+        let numero = valor.trim().trim_start_matches('+');
+        if valor.trim().is_empty() {
+            self.campo(campo, "campo obrigatorio");
+        } else if !apenas_numerico(numero) {
+            self.campo(campo, "telefone invalido: use DDD e numero");
+        } else if let Err(mensagem) = normalizar_telefone(numero) {
+            self.campo(campo, mensagem);
+        }
+    }
+
+    pub fn url(&mut self, campo: &str, valor: &str) {
+        if valor.trim().is_empty() {
+            self.campo(campo, "campo obrigatorio");
+        } else if valor.len() > MAX_URL || !url_valida(valor.trim()) {
+            self.campo(campo, "url invalida: use http:// ou https://");
+        }
+    }
+
+    // objeto json com tamanho limitado
+    pub fn metadados(&mut self, campo: &str, valor: Option<&Value>) {
+        match valor {
+            None | Some(Value::Null) => {}
+            Some(Value::Object(_)) => {
+                if valor.map_or(0, |v| v.to_string().len()) > MAX_METADADOS {
+                    self.campo(campo, format!("maximo de {} KB", MAX_METADADOS / 1024));
+                }
+            }
+            Some(_) => self.campo(campo, "deve ser um objeto json"),
+        }
+    }
+
+    pub fn vazio(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn into_result(self) -> Result<(), AppError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Campos(self.0))
+        }
+    }
+}
+
+fn digitos(valor: &str) -> String {
+    valor.chars().filter(|c| c.is_ascii_digit()).collect()
+}
+
+// somente digitos e a pontuacao usual de documentos e telefones
+fn apenas_numerico(valor: &str) -> bool {
+    valor.chars().all(|c| c.is_ascii_digit() || " .-/()".contains(c))
+}
+
+fn digito_verificador(numeros: &[u32], pesos: &[u32]) -> u32 {
+    let soma: u32 = numeros.iter().zip(pesos).map(|(n, p)| n * p).sum();
+    match soma % 11 {
+        0 | 1 => 0,
+        resto => 11 - resto,
+    }
+}
+
+pub fn cpf_valido(cpf: &str) -> bool {
+    let n: Vec<u32> = cpf.chars().filter_map(|c| c.to_digit(10)).collect();
+    if n.len() != 11 || n.iter().all(|d| *d == n[0]) {
+        return false;
+    }
+    digito_verificador(&n[..9], &[10, 9, 8, 7, 6, 5, 4, 3, 2]) == n[9]
+        && digito_verificador(&n[..10], &[11, 10, 9, 8, 7, 6, 5, 4, 3, 2]) == n[10]
+}
+
+pub fn cnpj_valido(cnpj: &str) -> bool {
+    let n: Vec<u32> = cnpj.chars().filter_map(|c| c.to_digit(10)).collect();
+    if n.len() != 14 || n.iter().all(|d| *d == n[0]) {
+        return false;
+    }
+    digito_verificador(&n[..12], &[5, 4, 3, 2, 9, 8, 7, 6, 5, 4, 3, 2]) == n[12]
+        && digito_verificador(&n[..13], &[6, 5, 4, 3, 2, 9, 8, 7, 6, 5, 4, 3, 2]) == n[13]
+}
+
+// CNPJ somente com digitos
+pub fn normalizar_cnpj(cnpj: &str) -> Result<String, String> {
+    if !apenas_numerico(cnpj) || !cnpj_valido(cnpj) {
+        return Err("cnpj invalido".into());
+    }
+    Ok(digitos(cnpj))
+}
+
+// Somente digitos, sem o 55 do pais e sem o 0 de discagem: DDD + numero.
+// E a chave de comparacao de telefones (duplicatas) e a base do formato E.164
+pub fn telefone_nacional(telefone: &str) -> String {
+    let mut d = digitos(telefone);
+    if d.len() >= 12 && d.starts_with("55") {
+        d.drain(..2);
+    }
+    d.trim_start_matches('0').to_string()
+}
+
+// Telefone brasileiro em E.164 (+55 DDD numero)
+pub fn normalizar_telefone(telefone: &str) -> Result<String, String> {
+    let d = telefone_nacional(telefone);
+
+    // DDDs vao de 11 a 99 e nao tem 0
+    if !(d.len() == 10 || d.len() == 11) || d[..2].contains('0') {
+        return Err("telefone invalido: use DDD e numero".into());
+    }
+    if d.len() == 11 && d.as_bytes()[2] != b'9' {
+        return Err("telefone invalido: celular deve comecar com 9".into());
+    }
+    Ok(format!("+55{d}"))
+}
+
+pub fn email_valido(email: &str) -> bool {
+    let Some((usuario, dominio)) = email.split_once('@') else { return false };
+    !usuario.is_empty()
+        && !email.chars().any(char::is_whitespace)
+        && !dominio.contains('@')
+        && dominio.split('.').count() >= 2
+        && dominio.split('.').all(|parte| !parte.is_empty())
+}
+
+// http(s) com host; o resto da url e livre
+pub fn url_valida(url: &str) -> bool {
+    let Some(resto) = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://")) else {
+        return false;
+    };
+    let host = resto.split(['/', '?', '#']).next().unwrap_or_default();
+    !url.chars().any(char::is_whitespace)
+        && !host.is_empty()
+        && host.chars().all(|c| c.is_alphanumeric() || ".-:[]".contains(c))
+}
+
+fn chave_aleatoria_valida(chave: &str) -> bool {
+    let partes: Vec<&str> = chave.split('-').collect();
+    partes.len() == 5
+        && partes.iter().zip([8, 4, 4, 4, 12]).all(|(p, tamanho)| p.len() == tamanho && p.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+// Reconhece o tipo da chave pix e devolve o valor normalizado:
+// cpf/cnpj so digitos, email e aleatoria em minusculas e telefone em E.164
+pub fn normalizar_chave_pix(chave: &str) -> Result<(TipoChavePix, String), String> {
+    let chave = chave.trim();
+
+    if chave.contains('@') {
+        return if email_valido(chave) {
+            Ok((TipoChavePix::Email, chave.to_lowercase()))
+        } else {
+            Err("chave pix de email invalida".into())
+        };
+    }
+
+    if chave_aleatoria_valida(chave) {
+        return Ok((TipoChavePix::Aleatoria, chave.to_lowercase()));
+    }
+
+    // telefone no formato da chave pix: +55...
+    if let Some(resto) = chave.strip_prefix('+') {
+        if !apenas_numerico(resto) {
+            return Err("chave pix de telefone invalida".into());
+        }
+        return normalizar_telefone(resto).map(|t| (TipoChavePix::Telefone, t));
+    }
+
+    if !apenas_numerico(chave) {
+        return Err("chave pix invalida: use cpf, cnpj, email, telefone ou chave aleatoria".into());
+    }
+
+    let d = digitos(chave);
+    match d.len() {
+        14 if cnpj_valido(&d) => Ok((TipoChavePix::Cnpj, d)),
+        14 => Err("cnpj invalido".into()),
+        11 if cpf_valido(&d) => Ok((TipoChavePix::Cpf, d)),
+        // pontuado como cpf (000.000.000-00) nao e telefone
+        11 if chave.contains('.') => Err("cpf invalido".into()),
+        // 11 digitos que nao formam cpf podem ser um celular com DDD
+        10..=13 => normalizar_telefone(&d)
+            .map(|t| (TipoChavePix::Telefone, t))
+            .map_err(|_| "chave pix invalida: cpf ou telefone invalido".into()),
+        _ => Err("chave pix invalida: use cpf, cnpj, email, telefone ou chave aleatoria".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpf_confere_os_digitos_verificadores() {
+        assert!(cpf_valido("529.982.247-25"));
+        assert!(cpf_valido("11144477735"));
+        assert!(!cpf_valido("529.982.247-24"));
+        assert!(!cpf_valido("1114447773"));
+        // digitos repetidos passam na conta mas nao sao cpf
+        assert!(!cpf_valido("111.111.111-11"));
+        assert!(!cpf_valido("00000000000"));
+    }
+
+    #[test]
+    fn cnpj_confere_os_digitos_verificadores() {
+        assert!(cnpj_valido("11.222.333/0001-81"));
+        assert!(cnpj_valido("11444777000161"));
+        assert!(!cnpj_valido("11.222.333/0001-80"));
+        assert!(!cnpj_valido("1122233300018"));
+        assert!(!cnpj_valido("00.000.000/0000-00"));
+        assert!(!cnpj_valido("11111111111111"));
+    }
+
+    #[test]
+    fn chave_pix_reconhece_o_tipo() {
+        let casos = [
+            ("529.982.247-25", TipoChavePix::Cpf, "52998224725"),
+            ("11.222.333/0001-81", TipoChavePix::Cnpj, "11222333000181"),
+            (" Doacoes@Exemplo.org ", TipoChavePix::Email, "doacoes@exemplo.org"),
+            ("+55 32 98888-0000", TipoChavePix::Telefone, "+5532988880000"),
+            ("(32) 98888-0000", TipoChavePix::Telefone, "+5532988880000"),
+            ("(32) 3333-4444", TipoChavePix::Telefone, "+553233334444"),
+            ("123E4567-E89B-12D3-A456-426614174000", TipoChavePix::Aleatoria, "123e4567-e89b-12d3-a456-426614174000"),
+        ];
+        for (chave, tipo, normalizada) in casos {
+            assert_eq!(normalizar_chave_pix(chave), Ok((tipo, normalizada.to_string())), "{chave}");
+        }
+    }
+
+    #[test]
+    fn chave_pix_de_11_digitos_sem_cpf_e_telefone() {
+        // 32988880000 nao fecha os digitos de cpf
+        assert!(!cpf_valido("32988880000"));
+        assert_eq!(normalizar_chave_pix("32988880000").map(|(tipo, _)| tipo), Ok(TipoChavePix::Telefone));
+        // mas pontuado como cpf continua sendo cpf
+        assert_eq!(normalizar_chave_pix("329.888.800-00"), Err("cpf invalido".into()));
+    }
+
+    #[test]
+    fn chave_pix_invalida() {
+        for chave in ["x", "doacoes@", "a@b", "+55 32 8888", "+55 (32) 98888-000a", "11.222.333/0001-80", "123", "123e4567-e89b-12d3-a456-42661417400"] {
+            assert!(normalizar_chave_pix(chave).is_err(), "{chave}");
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Fluxo de atendimento de uma solicitacao (pedido de ajuda)
+//
+// NOVO -> EM_ATENDIMENTO -> ATENDIDO
+//   \          |
+//    +--> CANCELADO / DUPLICADO
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StatusSolicitacao {
+    Novo,
+    EmAtendimento,
+    Atendido,
+    Cancelado,
+    Duplicado,
+}
+
+impl StatusSolicitacao {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusSolicitacao::Novo => "NOVO",
+            StatusSolicitacao::EmAtendimento => "EM_ATENDIMENTO",
+            StatusSolicitacao::Atendido => "ATENDIDO",
+            StatusSolicitacao::Cancelado => "CANCELADO",
+            StatusSolicitacao::Duplicado => "DUPLICADO",
+        }
+    }
+
+    pub fn pode_ir_para(&self, destino: StatusSolicitacao) -> bool {
+        use StatusSolicitacao::*;
+        matches!(
+            (self, destino),
+            (Novo, EmAtendimento)
+                | (Novo, Cancelado)
+                | (Novo, Duplicado)
+                | (EmAtendimento, Atendido)
+                | (EmAtendimento, Cancelado)
+                | (EmAtendimento, Duplicado)
+                // volta para a fila quando o voluntario desiste
+                | (EmAtendimento, Novo)
+                // reabertura de pedidos encerrados por engano
+                | (Cancelado, Novo)
+                | (Duplicado, Novo)
+        )
+    }
+}
+
+impl std::fmt::Display for StatusSolicitacao {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for StatusSolicitacao {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "NOVO" => Ok(StatusSolicitacao::Novo),
+            "EM_ATENDIMENTO" => Ok(StatusSolicitacao::EmAtendimento),
+            "ATENDIDO" => Ok(StatusSolicitacao::Atendido),
+            "CANCELADO" => Ok(StatusSolicitacao::Cancelado),
+            "DUPLICADO" => Ok(StatusSolicitacao::Duplicado),
+            outro => Err(format!("status desconhecido: {outro}")),
+        }
+    }
+}
+
+// Ciclo de vida de uma atribuicao de voluntario
+//
+// PENDENTE -> ACEITA -> EM_ANDAMENTO -> CONCLUIDA
+//    |          |            |
+//    +-> RECUSADA            |
+//    +----------+------------+--> CANCELADA
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StatusAtribuicao {
+    Pendente,
+    Aceita,
+    Recusada,
+    EmAndamento,
+    Concluida,
+    Cancelada,
+}
+
+impl StatusAtribuicao {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusAtribuicao::Pendente => "PENDENTE",
+            StatusAtribuicao::Aceita => "ACEITA",
+            StatusAtribuicao::Recusada => "RECUSADA",
+            StatusAtribuicao::EmAndamento => "EM_ANDAMENTO",
+            StatusAtribuicao::Concluida => "CONCLUIDA",
+            StatusAtribuicao::Cancelada => "CANCELADA",
+        }
+    }
+
+    pub fn pode_ir_para(&self, destino: StatusAtribuicao) -> bool {
+        use StatusAtribuicao::*;
+        matches!(
+            (self, destino),
+            (Pendente, Aceita)
+                | (Pendente, Recusada)
+                | (Pendente, Cancelada)
+                | (Aceita, EmAndamento)
+                | (Aceita, Cancelada)
+                | (EmAndamento, Concluida)
+                | (EmAndamento, Cancelada)
+        )
+    }
+
+    // coluna com o horario em que a atribuicao entrou no status
+    pub fn coluna_horario(&self) -> Option<&'static str> {
+        match self {
+            StatusAtribuicao::Pendente => None,
+            StatusAtribuicao::Aceita => Some("aceita_em"),
+            StatusAtribuicao::Recusada => Some("recusada_em"),
+            StatusAtribuicao::EmAndamento => Some("iniciada_em"),
+            StatusAtribuicao::Concluida => Some("concluida_em"),
+            StatusAtribuicao::Cancelada => Some("cancelada_em"),
+        }
+    }
+}
+
+impl std::fmt::Display for StatusAtribuicao {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for StatusAtribuicao {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PENDENTE" => Ok(StatusAtribuicao::Pendente),
+            "ACEITA" => Ok(StatusAtribuicao::Aceita),
+            "RECUSADA" => Ok(StatusAtribuicao::Recusada),
+            "EM_ANDAMENTO" => Ok(StatusAtribuicao::EmAndamento),
+            "CONCLUIDA" => Ok(StatusAtribuicao::Concluida),
+            "CANCELADA" => Ok(StatusAtribuicao::Cancelada),
+            outro => Err(format!("status desconhecido: {outro}")),
+        }
+    }
+}
+
+// Triagem de uma denuncia (golpe do pix, pagina falsa)
+//
+// RECEBIDA -> EM_ANALISE -> CONFIRMADA
+//     \           |            |
+//      +-----> DESCARTADA <----+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StatusDenuncia {
+    Recebida,
+    EmAnalise,
+    Confirmada,
+    Descartada,
+}
+
+impl StatusDenuncia {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusDenuncia::Recebida => "RECEBIDA",
+            StatusDenuncia::EmAnalise => "EM_ANALISE",
+            StatusDenuncia::Confirmada => "CONFIRMADA",
+            StatusDenuncia::Descartada => "DESCARTADA",
+        }
+    }
+
+    pub fn pode_ir_para(&self, destino: StatusDenuncia) -> bool {
+        use StatusDenuncia::*;
+        matches!(
+            (self, destino),
+            (Recebida, EmAnalise)
+                | (Recebida, Confirmada)
+                | (Recebida, Descartada)
+                | (EmAnalise, Confirmada)
+                | (EmAnalise, Descartada)
+                // confirmacao revista (chave devolvida ao dono legitimo)
+                | (Confirmada, Descartada)
+                // reabertura quando surgem novas evidencias
+                | (Descartada, EmAnalise)
+        )
+    }
+}
+
+impl std::fmt::Display for StatusDenuncia {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for StatusDenuncia {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "RECEBIDA" => Ok(StatusDenuncia::Recebida),
+            "EM_ANALISE" => Ok(StatusDenuncia::EmAnalise),
+            "CONFIRMADA" => Ok(StatusDenuncia::Confirmada),
+            "DESCARTADA" => Ok(StatusDenuncia::Descartada),
+            outro => Err(format!("status desconhecido: {outro}")),
+        }
+    }
+}
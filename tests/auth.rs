@@ -0,0 +1,143 @@
+// Cadastro, login, renovacao e logout de usuarios (Bearer)
+mod common;
+
+use axum::http::{Method, StatusCode};
+use serde_json::json;
+use sqlx::PgPool;
+
+use common::{app, chave, enviar, login, registrar, router, usuario_com_papel, Credencial};
+
+#[sqlx::test(migrations = "./migrations")]
+async fn cadastro_e_login(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    app(&pool, "app-b", "private", false).await;
+    let router = router(pool);
+
+    let id = registrar(&router, "ana@exemplo.org", "app-a").await;
+    assert!(!id.is_empty());
+
+    // mesmo email no mesmo app: 409; em outro app e outro usuario
+    let corpo = json!({ "nome": "Ana", "email": "ana@exemplo.org", "senha": "senha-forte", "app_id": "app-a" });
+    let (status, body) = enviar(&router, Method::POST, "/register", Credencial::Nenhuma, Some(corpo)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "conflict");
+    registrar(&router, "ana@exemplo.org", "app-b").await;
+
+    let corpo = json!({ "nome": "", "email": "ana", "senha": "123" });
+    let (status, body) = enviar(&router, Method::POST, "/register", Credencial::Nenhuma, Some(corpo)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let campos: Vec<&str> = body["campos"].as_array().unwrap().iter().map(|c| c["campo"].as_str().unwrap()).collect();
+    assert_eq!(campos, vec!["nome", "email", "senha"]);
+
+    let body = login(&router, "ana@exemplo.org", "app-a").await;
+    assert!(body["token"].is_string());
+    assert!(body["refresh_token"].is_string());
+    assert!(body["expires_in"].as_i64().unwrap() > 0);
+    assert!(body.get("senha").is_none());
+
+    for corpo in [
+        json!({ "email": "ana@exemplo.org", "senha": "errada", "app_id": "app-a" }),
+        json!({ "email": "ninguem@exemplo.org", "senha": "senha-forte" }),
+    ] {
+        let (status, body) = enviar(&router, Method::POST, "/login", Credencial::Nenhuma, Some(corpo)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["code"], "unauthorized");
+    }
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn rotas_privadas_exigem_credencial(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let router = router(pool);
+
+    for credencial in [Credencial::Nenhuma, Credencial::Bearer("nao-e-um-jwt")] {
+        let (status, body) = enviar(&router, Method::GET, "/pontos", credencial, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["code"], "unauthorized");
+    }
+
+    // publicas
+    let (status, _) = enviar(&router, Method::GET, "/ping", Credencial::Nenhuma, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = enviar(&router, Method::GET, "/apps/view", Credencial::Nenhuma, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 1);
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn papel_do_usuario_define_o_acesso(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let admin = chave(&pool, "app-a").await;
+    let router = router(pool);
+
+    // sem papel no app: autenticado, mas sem acesso
+    registrar(&router, "sem-papel@exemplo.org", "app-a").await;
+    let token = login(&router, "sem-papel@exemplo.org", "app-a").await["token"].as_str().unwrap().to_string();
+    let (status, body) = enviar(&router, Method::GET, "/pontos", Credencial::Bearer(&token), None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "forbidden");
+
+    let ponto = json!({ "nome": "Ponto", "telefone": "(32) 98888-1111", "bairro": "Centro" });
+
+    let visualizador = usuario_com_papel(&router, &admin, "ver@exemplo.org", "app-a", "VISUALIZADOR").await;
+    let (status, _) = enviar(&router, Method::GET, "/pontos", Credencial::Bearer(&visualizador), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = enviar(&router, Method::POST, "/pontos", Credencial::Bearer(&visualizador), Some(ponto.clone())).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let voluntario = usuario_com_papel(&router, &admin, "vol@exemplo.org", "app-a", "VOLUNTARIO").await;
+    let (status, body) = enviar(&router, Method::POST, "/pontos", Credencial::Bearer(&voluntario), Some(ponto)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    // listar usuarios e para coordenadores
+    let (status, _) = enviar(&router, Method::GET, "/users", Credencial::Bearer(&voluntario), None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let coordenador = usuario_com_papel(&router, &admin, "coord@exemplo.org", "app-a", "COORDENADOR").await;
+    let (status, body) = enviar(&router, Method::GET, "/users", Credencial::Bearer(&coordenador), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 4);
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn refresh_token_e_de_uso_unico(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let router = router(pool);
+
+    registrar(&router, "ana@exemplo.org", "app-a").await;
+    let primeiro = login(&router, "ana@exemplo.org", "app-a").await["refresh_token"].as_str().unwrap().to_string();
+
+    let (status, body) = enviar(&router, Method::POST, "/token/refresh", Credencial::Nenhuma, Some(json!({ "refresh_token": primeiro }))).await;
+    assert_eq!(status, StatusCode::OK);
+    let segundo = body["refresh_token"].as_str().unwrap().to_string();
+    assert_ne!(primeiro, segundo);
+
+    // reuso do token antigo derruba a sessao inteira, inclusive o token novo
+    let (status, _) = enviar(&router, Method::POST, "/token/refresh", Credencial::Nenhuma, Some(json!({ "refresh_token": primeiro }))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = enviar(&router, Method::POST, "/token/refresh", Credencial::Nenhuma, Some(json!({ "refresh_token": segundo }))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = enviar(&router, Method::POST, "/token/refresh", Credencial::Nenhuma, Some(json!({ "refresh_token": "lixo" }))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn logout_revoga_o_token(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let admin = chave(&pool, "app-a").await;
+    let router = router(pool);
+
+    let token = usuario_com_papel(&router, &admin, "ana@exemplo.org", "app-a", "VISUALIZADOR").await;
+    let (status, _) = enviar(&router, Method::GET, "/pontos", Credencial::Bearer(&token), None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = enviar(&router, Method::POST, "/logout", Credencial::Bearer(&token), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = enviar(&router, Method::GET, "/pontos", Credencial::Bearer(&token), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // apps nao tem sessao para encerrar
+    let (status, body) = enviar(&router, Method::POST, "/logout", Credencial::Chave(&admin), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "bad_request");
+}
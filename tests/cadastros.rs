@@ -0,0 +1,427 @@
+// Cadastro e listagem dos recursos de cada app, pela chave secreta
+mod common;
+
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use common::{app, chave, chave_publica, enviar, get, post, router, usuario_com_papel, Credencial};
+
+fn campos(body: &Value) -> Vec<&str> {
+    body["campos"].as_array().unwrap().iter().map(|c| c["campo"].as_str().unwrap()).collect()
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn pontos_e_itens(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let chave = chave(&pool, "app-a").await;
+    let router = router(pool);
+
+    let (status, body) = post(&router, "/pontos", &chave, json!({ "nome": "", "telefone": "123", "bairro": "Centro", "pix": "x" })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "validation_failed");
+    assert_eq!(campos(&body), vec!["nome", "telefone", "pix"]);
+
+    let novo = json!({
+        "nome": "Paroquia Centro",
+        "telefone": "(32) 98888-1111",
+        "bairro": "Centro",
+        "municipio": "Juiz de Fora",
+        "gps": "-21.7642, -43.3496",
+        "pix": "Doacoes@Exemplo.org",
+        "metadados": { "responsavel": "Maria" }
+    });
+    let (status, ponto) = post(&router, "/pontos", &chave, novo).await;
+    assert_eq!(status, StatusCode::OK, "{ponto}");
+    assert_eq!(ponto["pix"], "doacoes@exemplo.org");
+    assert_eq!(ponto["origem"], "app-a");
+    assert!(ponto.get("app_id").is_none());
+    let uid = ponto["uid"].as_str().unwrap();
+
+    let (status, body) = get(&router, "/pontos", &chave).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["data"][0]["uid"], uid);
+
+    let (status, body) = get(&router, "/pontos/nearby?lat=-21.76&lon=-43.35&radius_km=5", &chave).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 1);
+    assert!(body["data"][0]["distancia_km"].as_f64().unwrap() < 5.0);
+
+    let (status, body) = enviar(&router, Method::PATCH, &format!("/pontos/{uid}"), Credencial::Chave(&chave), Some(json!({ "nome": "Paroquia" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["nome"], "Paroquia");
+
+    // voto e de pessoas, nao de apps
+    let (status, _) = post(&router, &format!("/pontos/{uid}/votos"), &chave, json!({})).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let token = usuario_com_papel(&router, &chave, "ana@exemplo.org", "app-a", "VISUALIZADOR").await;
+    let (status, body) = enviar(&router, Method::POST, &format!("/pontos/{uid}/votos"), Credencial::Bearer(&token), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["votos"], 1);
+
+    let (status, _) = enviar(&router, Method::PUT, &format!("/pontos/{uid}/itens/agua"), Credencial::Chave(&chave), Some(json!({ "aceitando": true, "estoque": 2, "estoque_minimo": 10 }))).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = post(&router, &format!("/pontos/{uid}/itens/agua/ajuste"), &chave, json!({ "quantidade": 3 })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["estoque"], 5);
+
+    let (status, body) = get(&router, &format!("/pontos/{uid}/itens"), &chave).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body[0]["item"], "AGUA");
+
+    let (status, body) = get(&router, "/necessidades", &chave).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body[0]["bairro"], "Centro");
+    assert_eq!(body[0]["estoque_baixo"][0]["item"], "AGUA");
+
+    let (status, body) = get(&router, "/pontos.geojson", &chave).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["features"].as_array().unwrap().len(), 1);
+
+    let (status, _) = enviar(&router, Method::DELETE, &format!("/pontos/{uid}"), Credencial::Chave(&chave), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, body) = get(&router, &format!("/pontos/{uid}"), &chave).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "not_found");
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn voluntarios_solicitacoes_e_atribuicoes(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let chave = chave(&pool, "app-a").await;
+    let router = router(pool);
+
+    let (status, body) = post(&router, "/voluntarios", &chave, json!({ "nome": "Joao", "telefone": "32", "mensagem": "" })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(campos(&body), vec!["telefone", "mensagem"]);
+
+    // o voluntario se cadastra com o proprio usuario e fica ligado a ele
+    let joao = usuario_com_papel(&router, &chave, "joao@exemplo.org", "app-a", "VOLUNTARIO").await;
+    let maria = usuario_com_papel(&router, &chave, "maria@exemplo.org", "app-a", "VOLUNTARIO").await;
+    let (status, voluntario) = enviar(&router, Method::POST, "/voluntarios", Credencial::Bearer(&joao), Some(json!({
+        "nome": "Joao", "telefone": "+55 32 99999-2222", "municipio": "Juiz de Fora", "mensagem": "tenho carro"
+    }))).await;
+    assert_eq!(status, StatusCode::OK, "{voluntario}");
+    assert!(voluntario["user_id"].is_string());
+
+    let (status, solicitacao) = post(&router, "/solicitacoes", &chave, json!({
+        "nome": "Familia Silva", "telefone": "32 97777-3333", "municipio": "Juiz de Fora", "mensagem": "precisamos de agua"
+    })).await;
+    assert_eq!(status, StatusCode::OK, "{solicitacao}");
+    assert_eq!(solicitacao["status"], "NOVO");
+    let uid = solicitacao["uid"].as_str().unwrap();
+
+    for uri in ["/voluntarios", "/solicitacoes"] {
+        let (status, body) = get(&router, uri, &chave).await;
+        assert_eq!(status, StatusCode::OK, "{uri}");
+        assert_eq!(body["total"], 1, "{uri}");
+    }
+
+    let (status, body) = get(&router, &format!("/solicitacoes/{uid}/candidatos"), &chave).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body[0]["uid"], voluntario["uid"]);
+
+    let atribuir = json!({ "voluntario_uid": voluntario["uid"] });
+    let (status, atribuicao) = post(&router, &format!("/solicitacoes/{uid}/atribuicoes"), &chave, atribuir.clone()).await;
+    assert_eq!(status, StatusCode::OK, "{atribuicao}");
+    assert_eq!(atribuicao["status"], "PENDENTE");
+    let (status, body) = post(&router, &format!("/solicitacoes/{uid}/atribuicoes"), &chave, atribuir).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "conflict");
+
+    // so o usuario do voluntario atribuido aceita; nem a chave do app nem outro voluntario
+    let status_uri = format!("/atribuicoes/{}/status", atribuicao["uid"].as_str().unwrap());
+    let (status, _) = post(&router, &status_uri, &chave, json!({ "status": "ACEITA" })).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = enviar(&router, Method::POST, &status_uri, Credencial::Bearer(&maria), Some(json!({ "status": "ACEITA" }))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, body) = enviar(&router, Method::POST, &status_uri, Credencial::Bearer(&joao), Some(json!({ "status": "ACEITA" }))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["status"], "ACEITA");
+
+    let (_, body) = get(&router, &format!("/solicitacoes/{uid}/atribuicoes"), &chave).await;
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    let (_, body) = get(&router, "/solicitacoes", &chave).await;
+    assert_eq!(body["data"][0]["voluntario_atual"]["uid"], voluntario["uid"]);
+
+    let (status, body) = post(&router, &format!("/solicitacoes/{uid}/status"), &chave, json!({ "status": "ATENDIDO", "observacao": "entregue" })).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["status"], "ATENDIDO");
+    assert_eq!(body["voluntario_atual"]["uid"], voluntario["uid"]);
+    let (status, body) = get(&router, &format!("/solicitacoes/{uid}/historico"), &chave).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().last().unwrap()["status_novo"], "ATENDIDO");
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn segundo_voluntario_conclui_pedido_ja_atendido(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let chave = chave(&pool, "app-a").await;
+    let router = router(pool);
+
+    let (_, solicitacao) = post(&router, "/solicitacoes", &chave, json!({
+        "nome": "Familia Silva", "telefone": "32 97777-3333", "municipio": "Juiz de Fora", "mensagem": "precisamos de agua"
+    })).await;
+    let uid = solicitacao["uid"].as_str().unwrap();
+
+    let mut voluntarios = Vec::new();
+    for (email, telefone) in [("joao@exemplo.org", "32999992222"), ("maria@exemplo.org", "32999993333")] {
+        let token = usuario_com_papel(&router, &chave, email, "app-a", "VOLUNTARIO").await;
+        let (_, voluntario) = enviar(&router, Method::POST, "/voluntarios", Credencial::Bearer(&token), Some(json!({
+            "nome": email, "telefone": telefone, "municipio": "Juiz de Fora", "mensagem": "ajudo"
+        }))).await;
+        let (status, atribuicao) = post(&router, &format!("/solicitacoes/{uid}/atribuicoes"), &chave, json!({ "voluntario_uid": voluntario["uid"] })).await;
+        assert_eq!(status, StatusCode::OK, "{atribuicao}");
+        let status_uri = format!("/atribuicoes/{}/status", atribuicao["uid"].as_str().unwrap());
+        for novo in ["ACEITA", "EM_ANDAMENTO"] {
+            let (status, body) = enviar(&router, Method::POST, &status_uri, Credencial::Bearer(&token), Some(json!({ "status": novo }))).await;
+            assert_eq!(status, StatusCode::OK, "{body}");
+        }
+        voluntarios.push((token, status_uri));
+    }
+
+    // os dois concluem pedindo para encerrar o pedido; o segundo encontra o pedido ja atendido
+    for (token, status_uri) in &voluntarios {
+        let (status, body) = enviar(&router, Method::POST, status_uri, Credencial::Bearer(token), Some(json!({ "status": "CONCLUIDA", "atender_solicitacao": true }))).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["status"], "CONCLUIDA");
+    }
+    let (_, body) = get(&router, &format!("/solicitacoes/{uid}/historico"), &chave).await;
+    let atendido = body.as_array().unwrap().iter().filter(|h| h["status_novo"] == "ATENDIDO").count();
+    assert_eq!(atendido, 1);
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn mesclar_entre_apps_leva_os_votos_para_o_original(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    app(&pool, "app-b", "private", false).await;
+    let original: String = sqlx::query_scalar("INSERT INTO solicitacoes (app_id, nome, telefone, telefone_normalizado, municipio, mensagem) VALUES ('app-b', 'Familia Silva', '+5532977773333', '32977773333', 'Juiz de Fora', 'agua') RETURNING uid")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let chave = chave(&pool, "app-a").await;
+    let router = router(pool.clone());
+
+    let (_, duplicata) = post(&router, "/solicitacoes", &chave, json!({
+        "nome": "Familia da Silva", "telefone": "32 97777-3333", "mensagem": "precisamos de agua"
+    })).await;
+    assert_eq!(duplicata["duplicada_de"], original.as_str());
+    let uid = duplicata["uid"].as_str().unwrap();
+
+    let eleitor = usuario_com_papel(&router, &chave, "eleitor@exemplo.org", "app-a", "VISUALIZADOR").await;
+    let (status, _) = enviar(&router, Method::POST, &format!("/solicitacoes/{uid}/votos"), Credencial::Bearer(&eleitor), None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = post(&router, &format!("/solicitacoes/{uid}/mesclar"), &chave, json!({})).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["status"], "DUPLICADO");
+
+    // o voto migrado pertence ao app do original
+    let votos: Vec<(String, String)> = sqlx::query_as("SELECT app_id, solicitacao_id FROM votos_solicitacoes")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(votos, vec![("app-b".to_string(), original.clone())]);
+    let contagem: i32 = sqlx::query_scalar("SELECT votos FROM solicitacoes WHERE uid = $1").bind(&original).fetch_one(&pool).await.unwrap();
+    assert_eq!(contagem, 1);
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn denuncias_moderacao_e_evidencias(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let chave = chave(&pool, "app-a").await;
+    let router = router(pool.clone());
+
+    let (status, body) = post(&router, "/denuncias", &chave, json!({ "tipo": "pix", "url": "golpe.exemplo" })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(campos(&body), vec!["url"]);
+
+    let (status, denuncia) = post(&router, "/denuncias", &chave, json!({
+        "tipo": "pix", "url": "https://golpe.exemplo/doe", "chave_pix": "+55 32 98888-0000", "memorando": "site falso",
+        "created_by": "outra-pessoa"
+    })).await;
+    assert_eq!(status, StatusCode::OK, "{denuncia}");
+    assert_eq!(denuncia["chave_pix"], "+5532988880000");
+    // o autor vem da credencial, nao do corpo: a chave do app nao e um usuario
+    assert!(denuncia["created_by"].is_null(), "{denuncia}");
+    let uid = denuncia["uid"].as_str().unwrap();
+
+    let (_, body) = get(&router, "/denuncias", &chave).await;
+    assert_eq!(body["total"], 1);
+
+    for status_novo in ["EM_ANALISE", "CONFIRMADA"] {
+        let (status, body) = post(&router, &format!("/denuncias/{uid}/status"), &chave, json!({ "status": status_novo })).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+    }
+    let (_, body) = get(&router, &format!("/denuncias/{uid}/moderacao"), &chave).await;
+    assert_eq!(body.as_array().unwrap().len(), 2);
+
+    // confirmada por um app so, a chave ja vai para a lista publica
+    let (status, body) = enviar(&router, Method::GET, "/pix/32988880000/status", Credencial::Nenhuma, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["tipo"], "TELEFONE");
+    assert_eq!(body["bloqueada"], true);
+    assert_eq!(body["apps_que_confirmaram"], 1);
+    let (_, body) = enviar(&router, Method::GET, "/denuncias/blocklist", Credencial::Nenhuma, None).await;
+    assert_eq!(body["chaves_pix"], json!(["+5532988880000"]));
+    assert_eq!(body["urls"], json!(["https://golpe.exemplo/doe"]));
+    let (status, _) = post(&router, "/pontos", &chave, json!({ "nome": "Doacoes", "telefone": "32999990000", "bairro": "Centro", "pix": "32988880000" })).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let png = b"\x89PNG\r\n\x1a\n0000".to_vec();
+    let mut corpo = b"--limite\r\nContent-Disposition: form-data; name=\"arquivo\"; filename=\"print.png\"\r\nContent-Type: image/png\r\n\r\n".to_vec();
+    corpo.extend_from_slice(&png);
+    corpo.extend_from_slice(b"\r\n--limite--\r\n");
+    let res = router
+        .clone()
+        .oneshot(
+            Request::post(format!("/denuncias/{uid}/evidencias"))
+                .header("x-emergencial-key", &chave)
+                .header("content-type", "multipart/form-data; boundary=limite")
+                .body(Body::from(corpo))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body: Value = serde_json::from_slice(&res.into_body().collect().await.unwrap().to_bytes()).unwrap();
+    assert_eq!(body[0]["mime"], "image/png");
+
+    let (status, body) = get(&router, &format!("/denuncias/{uid}/evidencias"), &chave).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    // denuncia feita com o token de um usuario fica com o id dele
+    let token = usuario_com_papel(&router, &chave, "ana@exemplo.org", "app-a", "VISUALIZADOR").await;
+    let (status, body) = enviar(&router, Method::POST, "/denuncias", Credencial::Bearer(&token), Some(json!({
+        "tipo": "pix", "url": "https://golpe.exemplo/outra", "created_by": "outra-pessoa"
+    }))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let autor: String = sqlx::query_scalar("SELECT id FROM users WHERE email = 'ana@exemplo.org'").fetch_one(&pool).await.unwrap();
+    assert_eq!(body["created_by"], autor);
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn pontos_em_quarentena_saem_das_listagens(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let chave = chave(&pool, "app-a").await;
+    let chave_publica = chave_publica(&pool, "app-a").await;
+    let router = router(pool);
+
+    for (nome, pix) in [("Legitimo", "doacoes@entidade.org"), ("Golpe", "golpista@exemplo.com")] {
+        let (status, body) = post(&router, "/pontos", &chave, json!({
+            "nome": nome, "telefone": "32999990000", "bairro": "Centro", "gps": "-21.76,-43.35", "pix": pix, "itens": "AGUA"
+        })).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+    }
+    let (_, denuncia) = post(&router, "/denuncias", &chave, json!({ "tipo": "pix", "url": "https://golpe.exemplo", "chave_pix": "golpista@exemplo.com" })).await;
+    let (status, _) = post(&router, &format!("/denuncias/{}/status", denuncia["uid"].as_str().unwrap()), &chave, json!({ "status": "CONFIRMADA" })).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let nomes = |body: &Value, chave: &str| -> Vec<String> {
+        body[chave].as_array().unwrap().iter().map(|p| p["nome"].as_str().or(p["properties"]["nome"].as_str()).unwrap().to_string()).collect()
+    };
+    for (uri, lista) in [("/pontos", "data"), ("/pontos/nearby?lat=-21.76&lon=-43.35", "data"), ("/pontos.geojson", "features")] {
+        let (status, body) = get(&router, uri, &chave_publica).await;
+        assert_eq!(status, StatusCode::OK, "{uri}");
+        assert_eq!(nomes(&body, lista), vec!["Legitimo"], "{uri}");
+
+        // moderacao ainda ve os bloqueados quando pede
+        let com_bloqueados = format!("{uri}{}bloqueados=true", if uri.contains('?') { "&" } else { "?" });
+        let (status, _) = get(&router, &com_bloqueados, &chave_publica).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{uri}");
+        let (_, body) = get(&router, &com_bloqueados, &chave).await;
+        assert_eq!(nomes(&body, lista).len(), 2, "{uri}");
+    }
+    let (_, body) = get(&router, "/pontos", &chave_publica).await;
+    assert_eq!(body["total"], 1);
+    let (_, body) = get(&router, "/necessidades", &chave_publica).await;
+    assert_eq!(body[0]["aceitando"][0]["pontos"], 1);
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn chave_telefone_antiga_vai_para_e164(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let chave = chave(&pool, "app-a").await;
+    // gravados antes da validacao, sem o +55; o cpf pontuado continua cpf
+    let mut pontos = Vec::new();
+    for (nome, pix) in [("Antigo", "(32) 98888-0000"), ("Entidade", "529.982.247-25")] {
+        let uid: String = sqlx::query_scalar("INSERT INTO pontos (app_id, nome, telefone, bairro, pix) VALUES ('app-a', $1, '32999990000', 'Centro', $2) RETURNING uid")
+            .bind(nome).bind(pix).fetch_one(&pool).await.unwrap();
+        pontos.push(uid);
+    }
+    sqlx::query("INSERT INTO denuncias (app_id, url, memorando, chave_pix, status) VALUES ('app-a', 'https://golpe.exemplo', 'pix falso', '032 98888-0000', 'CONFIRMADA')")
+        .execute(&pool).await.unwrap();
+    sqlx::raw_sql(include_str!("../migrations/20261018280000_chave_pix_telefone_e164.sql"))
+        .execute(&pool).await.unwrap();
+
+    let pix: Vec<String> = sqlx::query_scalar("SELECT pix FROM pontos ORDER BY nome").fetch_all(&pool).await.unwrap();
+    assert_eq!(pix, vec!["+5532988880000", "529.982.247-25"]);
+    let denunciada: String = sqlx::query_scalar("SELECT chave_pix FROM denuncias").fetch_one(&pool).await.unwrap();
+    assert_eq!(denunciada, "+5532988880000");
+    let router = router(pool);
+
+    let (_, body) = get(&router, &format!("/pontos/{}", pontos[0]), &chave).await;
+    assert!(body["bloqueado_em"].is_string(), "{body}");
+    let (_, body) = get(&router, &format!("/pontos/{}", pontos[1]), &chave).await;
+    assert!(body["bloqueado_em"].is_null(), "{body}");
+    let (status, body) = post(&router, "/pontos", &chave, json!({ "nome": "Novo", "telefone": "32999990000", "bairro": "Centro", "pix": "32988880000" })).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn telefone_e_cpf_de_mesmos_digitos_sao_chaves_diferentes(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let chave = chave(&pool, "app-a").await;
+    let router = router(pool);
+
+    // cpf 529.982.247-25 e o celular (52) 99822-4725 tem os mesmos 11 digitos
+    let (status, ponto) = post(&router, "/pontos", &chave, json!({ "nome": "Entidade", "telefone": "32999990000", "bairro": "Centro", "pix": "529.982.247-25" })).await;
+    assert_eq!(status, StatusCode::OK, "{ponto}");
+    let (_, denuncia) = post(&router, "/denuncias", &chave, json!({ "tipo": "pix", "url": "https://golpe.exemplo", "chave_pix": "+55 52 99822-4725" })).await;
+    let (status, _) = post(&router, &format!("/denuncias/{}/status", denuncia["uid"].as_str().unwrap()), &chave, json!({ "status": "CONFIRMADA" })).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, body) = get(&router, &format!("/pontos/{}", ponto["uid"].as_str().unwrap()), &chave).await;
+    assert!(body["bloqueado_em"].is_null(), "{body}");
+    let (_, body) = enviar(&router, Method::GET, "/pix/52998224725/status", Credencial::Nenhuma, None).await;
+    assert_eq!(body["tipo"], "CPF");
+    assert_eq!(body["bloqueada"], false);
+    let (_, body) = enviar(&router, Method::GET, "/pix/+5552998224725/status", Credencial::Nenhuma, None).await;
+    assert_eq!(body["tipo"], "TELEFONE");
+    assert_eq!(body["bloqueada"], true);
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn usuarios_e_papeis(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let chave = chave(&pool, "app-a").await;
+    let router = router(pool);
+
+    let novo = json!({ "nome": "Ana", "email": "ana@exemplo.org", "senha": "senha-forte", "app_id": "app-a" });
+    let (status, usuario) = post(&router, "/users", &chave, novo).await;
+    assert_eq!(status, StatusCode::OK);
+    let id = usuario["id"].as_str().unwrap();
+
+    let (status, body) = get(&router, "/users", &chave).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    let (status, _) = enviar(&router, Method::PUT, &format!("/users/{id}/papel"), Credencial::Chave(&chave), Some(json!({ "papel": "COORDENADOR" }))).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, body) = get(&router, "/papeis", &chave).await;
+    assert_eq!(body[0]["papel"], "COORDENADOR");
+
+    let (status, _) = enviar(&router, Method::DELETE, &format!("/users/{id}/papel"), Credencial::Chave(&chave), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, body) = get(&router, "/papeis", &chave).await;
+    assert!(body.as_array().unwrap().is_empty());
+
+    let (status, body) = enviar(&router, Method::PUT, &format!("/users/{id}/papel"), Credencial::Chave(&chave), Some(json!({ "papel": "CHEFE" }))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "validation_failed");
+}
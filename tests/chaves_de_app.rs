@@ -0,0 +1,114 @@
+// Autenticacao dos apps pelo header X-Emergencial-Key
+mod common;
+
+use axum::http::{Method, StatusCode};
+use serde_json::json;
+use sqlx::PgPool;
+
+use common::{app, chave, chave_publica, enviar, get, login, post, registrar, router, Credencial};
+
+fn ponto() -> serde_json::Value {
+    json!({ "nome": "Ponto", "telefone": "(32) 98888-1111", "bairro": "Centro" })
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn chave_publica_e_somente_leitura(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let secreta = chave(&pool, "app-a").await;
+    let publica = chave_publica(&pool, "app-a").await;
+    let router = router(pool);
+
+    let (status, _) = post(&router, "/pontos", &secreta, ponto()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = get(&router, "/pontos", &publica).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 1);
+
+    let (status, body) = post(&router, "/pontos", &publica, ponto()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "forbidden");
+
+    // leitura de dados administrativos tambem exige a chave secreta
+    let (status, _) = get(&router, "/apps/keys", &publica).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn chave_invalida_revogada_ou_rotacionada(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let admin = chave(&pool, "app-a").await;
+    let router = router(pool);
+
+    for invalida in ["sk_inexistente.segredo", "qualquer coisa", &format!("{admin}x")] {
+        let (status, body) = get(&router, "/pontos", invalida).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{invalida}");
+        assert_eq!(body["code"], "unauthorized");
+    }
+
+    let (status, criada) = post(&router, "/apps/keys", &admin, json!({ "label": "frontend", "tipo": "secret" })).await;
+    assert_eq!(status, StatusCode::OK);
+    let nova = criada["chave"].as_str().unwrap().to_string();
+    let uid = criada["uid"].as_str().unwrap().to_string();
+    assert!(nova.starts_with("sk_"));
+
+    let (status, body) = get(&router, "/apps/keys", &admin).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().unwrap().len(), 2);
+    // o segredo nunca volta na listagem
+    assert!(body.as_array().unwrap().iter().all(|k| k.get("chave").is_none()));
+
+    let (status, rotacionada) = post(&router, &format!("/apps/keys/{uid}/rotate"), &admin, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let rotacionada = rotacionada["chave"].as_str().unwrap().to_string();
+    let (status, _) = get(&router, "/pontos", &nova).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = get(&router, "/pontos", &rotacionada).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let uid_rotacionada = get(&router, "/apps/keys", &admin).await.1.as_array().unwrap().iter()
+        .find(|k| k["revoked_at"].is_null() && k["label"] == "frontend")
+        .map(|k| k["uid"].as_str().unwrap().to_string())
+        .unwrap();
+    let (status, _) = enviar(&router, Method::DELETE, &format!("/apps/keys/{uid_rotacionada}"), Credencial::Chave(&admin), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = get(&router, "/pontos", &rotacionada).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn app_desativado_perde_o_acesso(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    let router = router(pool.clone());
+
+    registrar(&router, "root@exemplo.org", "app-a").await;
+    sqlx::query("UPDATE users SET super_admin = true WHERE email = 'root@exemplo.org'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let root = login(&router, "root@exemplo.org", "app-a").await["token"].as_str().unwrap().to_string();
+
+    // super admin cadastra o app e recebe a chave inicial
+    let novo = json!({ "email": "contato@cidade.org", "nome": "Cidade", "url": "https://cidade.org" });
+    let (status, criado) = enviar(&router, Method::POST, "/apps", Credencial::Bearer(&root), Some(novo)).await;
+    assert_eq!(status, StatusCode::OK, "{criado}");
+    let uid = criado["uid"].as_str().unwrap().to_string();
+    let chave_app = criado["chave"].as_str().unwrap().to_string();
+
+    let (status, _) = get(&router, "/pontos", &chave_app).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = enviar(&router, Method::POST, &format!("/apps/{uid}/disable"), Credencial::Bearer(&root), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = get(&router, "/pontos", &chave_app).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = enviar(&router, Method::POST, &format!("/apps/{uid}/enable"), Credencial::Bearer(&root), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = get(&router, "/pontos", &chave_app).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // apenas super admins gerenciam apps
+    let (status, _) = enviar(&router, Method::POST, &format!("/apps/{uid}/disable"), Credencial::Chave(&chave_app), None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,130 @@
+// Apoio dos testes de integracao: router sobre o banco temporario do sqlx::test,
+// cadastro direto de apps e chaves e requisicoes com cada tipo de credencial.
+// Cada arquivo em tests/ usa so uma parte destas funcoes.
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use http_body_util::BodyExt;
+use serde_json::Value;
+use sqlx::PgPool;
+use tower::ServiceExt;
+
+use emapi::api_keys::{create_key, ApiKeyCache, TipoChave};
+use emapi::armazenamento::Armazenamento;
+use emapi::denuncias::ConfigBloqueio;
+use emapi::handlers::AppState;
+use emapi::jwt::JwtConfig;
+use emapi::routes::create_router;
+
+pub fn router(pool: PgPool) -> Router {
+    router_com_bloqueio(pool, ConfigBloqueio::default())
+}
+
+pub fn router_com_bloqueio(pool: PgPool, bloqueio: ConfigBloqueio) -> Router {
+    let jwt = JwtConfig::from_env().expect("invalid JWT configuration");
+    let armazenamento = Armazenamento::local(std::env::temp_dir().join("emapi-evidencias")).expect("evidence dir");
+    create_router(AppState { db: pool, jwt: Arc::new(jwt), api_keys: ApiKeyCache::default(), armazenamento: Arc::new(armazenamento), bloqueio })
+}
+
+pub async fn app(pool: &PgPool, uid: &str, tipo: &str, federado: bool) {
+    sqlx::query("INSERT INTO app (uid, email, nome, tipo, url, federado) VALUES ($1, $2, $1, $3, 'https://exemplo.org', $4)")
+        .bind(uid)
+        .bind(format!("{uid}@exemplo.org"))
+        .bind(tipo)
+        .bind(federado)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+// chave secreta (age como admin do app)
+pub async fn chave(pool: &PgPool, app_id: &str) -> String {
+    create_key(pool, app_id, "teste", TipoChave::Secret).await.unwrap().chave
+}
+
+// chave publishable (somente leitura)
+pub async fn chave_publica(pool: &PgPool, app_id: &str) -> String {
+    create_key(pool, app_id, "teste", TipoChave::Publishable).await.unwrap().chave
+}
+
+#[derive(Clone, Copy)]
+pub enum Credencial<'a> {
+    Nenhuma,
+    Chave(&'a str),
+    Bearer(&'a str),
+}
+
+pub async fn enviar(router: &Router, metodo: Method, uri: &str, credencial: Credencial<'_>, corpo: Option<Value>) -> (StatusCode, Value) {
+    let mut req = Request::builder().method(metodo).uri(uri);
+    req = match credencial {
+        Credencial::Nenhuma => req,
+        Credencial::Chave(chave) => req.header("x-emergencial-key", chave),
+        Credencial::Bearer(token) => req.header("authorization", format!("Bearer {token}")),
+    };
+    let body = match corpo {
+        Some(corpo) => {
+            req = req.header("content-type", "application/json");
+            Body::from(corpo.to_string())
+        }
+        None => Body::empty(),
+    };
+
+    let res = router.clone().oneshot(req.body(body).unwrap()).await.unwrap();
+    let status = res.status();
+    let bytes = res.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+pub async fn get(router: &Router, uri: &str, chave: &str) -> (StatusCode, Value) {
+    enviar(router, Method::GET, uri, Credencial::Chave(chave), None).await
+}
+
+pub async fn post(router: &Router, uri: &str, chave: &str, corpo: Value) -> (StatusCode, Value) {
+    enviar(router, Method::POST, uri, Credencial::Chave(chave), Some(corpo)).await
+}
+
+// usuario cadastrado pela API; devolve o id
+pub async fn registrar(router: &Router, email: &str, app_id: &str) -> String {
+    let (status, body) = enviar(
+        router,
+        Method::POST,
+        "/register",
+        Credencial::Nenhuma,
+        Some(serde_json::json!({ "nome": "Pessoa", "email": email, "senha": "senha-forte", "app_id": app_id })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    body["id"].as_str().unwrap().to_string()
+}
+
+pub async fn login(router: &Router, email: &str, app_id: &str) -> Value {
+    let (status, body) = enviar(
+        router,
+        Method::POST,
+        "/login",
+        Credencial::Nenhuma,
+        Some(serde_json::json!({ "email": email, "senha": "senha-forte", "app_id": app_id })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    body
+}
+
+// usuario com papel no app, ja logado nele; devolve o access token
+pub async fn usuario_com_papel(router: &Router, chave_admin: &str, email: &str, app_id: &str, papel: &str) -> String {
+    let id = registrar(router, email, app_id).await;
+    let (status, body) = enviar(
+        router,
+        Method::PUT,
+        &format!("/users/{id}/papel"),
+        Credencial::Chave(chave_admin),
+        Some(serde_json::json!({ "papel": papel })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    login(router, email, app_id).await["token"].as_str().unwrap().to_string()
+}
@@ -0,0 +1,70 @@
+// Documento OpenAPI: toda rota de routes::create_router precisa estar no /openapi.json
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use http_body_util::BodyExt;
+use serde_json::Value;
+use sqlx::postgres::PgPoolOptions;
+use tower::ServiceExt;
+use utoipa::OpenApi;
+
+use emapi::api_keys::ApiKeyCache;
+use emapi::armazenamento::Armazenamento;
+use emapi::denuncias::ConfigBloqueio;
+use emapi::handlers::AppState;
+use emapi::jwt::JwtConfig;
+use emapi::openapi::ApiDoc;
+use emapi::routes::{create_router, ROTAS_PRIVADAS, ROTAS_PUBLICAS};
+
+#[test]
+fn todas_as_rotas_estao_documentadas() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+    let faltando: Vec<String> = ROTAS_PUBLICAS
+        .iter()
+        .chain(ROTAS_PRIVADAS)
+        .filter(|(metodo, caminho)| spec["paths"][*caminho][*metodo].is_null())
+        .map(|(metodo, caminho)| format!("{} {caminho}", metodo.to_uppercase()))
+        .collect();
+
+    assert!(faltando.is_empty(), "rotas sem documentacao OpenAPI (adicione em openapi.rs): {faltando:?}");
+}
+
+#[test]
+fn rotas_publicas_nao_exigem_credencial() {
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+    for (metodo, caminho) in [("post", "/register"), ("post", "/login"), ("get", "/apps/view"), ("get", "/pix/{chave}/status")] {
+        let seguranca = &spec["paths"][caminho][metodo]["security"];
+        assert_eq!(seguranca, &serde_json::json!([{}]), "{metodo} {caminho}");
+    }
+    assert!(spec["paths"]["/pontos"]["get"]["security"].is_null());
+    assert!(spec["security"].as_array().is_some_and(|s| !s.is_empty()));
+}
+
+#[tokio::test]
+async fn openapi_json_e_servido() {
+    // nenhuma consulta ao banco: o pool nunca chega a conectar
+    let pool = PgPoolOptions::new().connect_lazy("postgres://localhost/emapi").unwrap();
+    let jwt = JwtConfig::from_env().expect("invalid JWT configuration");
+    let armazenamento = Armazenamento::local(std::env::temp_dir().join("emapi-evidencias")).expect("evidence dir");
+    let router = create_router(AppState { db: pool, jwt: Arc::new(jwt), api_keys: ApiKeyCache::default(), armazenamento: Arc::new(armazenamento), bloqueio: ConfigBloqueio::default() });
+
+    let res = router
+        .clone()
+        .oneshot(Request::get("/openapi.json").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.into_body().collect().await.unwrap().to_bytes();
+    let spec: Value = serde_json::from_slice(&body).unwrap();
+    assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+    assert!(spec["components"]["schemas"]["NewPonto"].is_object());
+
+    let res = router
+        .oneshot(Request::get("/docs/").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
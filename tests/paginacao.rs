@@ -0,0 +1,114 @@
+// Paginacao das listagens: limites de per_page, paginas fora do intervalo e ordem
+mod common;
+
+use axum::http::StatusCode;
+use serde_json::Value;
+use sqlx::PgPool;
+
+use common::{app, chave, get, router};
+
+async fn solicitacoes(pool: &PgPool, app_id: &str, quantidade: usize) {
+    for i in 0..quantidade {
+        // created_at crescente para a ordem ser deterministica
+        sqlx::query("INSERT INTO solicitacoes (app_id, nome, telefone, mensagem, created_at) VALUES ($1, $2, '32990000001', 'agua', now() - make_interval(mins => $3))")
+            .bind(app_id)
+            .bind(format!("Pedido {i}"))
+            .bind((quantidade - i) as i32)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+}
+
+fn nomes(body: &Value) -> Vec<&str> {
+    body["data"].as_array().unwrap().iter().map(|s| s["nome"].as_str().unwrap()).collect()
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn paginas_sem_sobreposicao(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    solicitacoes(&pool, "app-a", 5).await;
+    let chave = chave(&pool, "app-a").await;
+    let router = router(pool);
+
+    let (status, body) = get(&router, "/solicitacoes", &chave).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["page"], 1);
+    assert_eq!(body["per_page"], 50);
+    assert_eq!(body["total"], 5);
+    // mais recentes primeiro
+    assert_eq!(nomes(&body)[0], "Pedido 4");
+
+    let mut vistos = Vec::new();
+    for page in 1..=3 {
+        let (_, body) = get(&router, &format!("/solicitacoes?per_page=2&page={page}"), &chave).await;
+        assert_eq!(body["page"], page);
+        assert_eq!(body["total"], 5);
+        vistos.extend(nomes(&body).into_iter().map(str::to_string));
+    }
+    assert_eq!(vistos, vec!["Pedido 4", "Pedido 3", "Pedido 2", "Pedido 1", "Pedido 0"]);
+
+    // alem da ultima pagina: vazio, mas com o total
+    let (status, body) = get(&router, "/solicitacoes?per_page=2&page=4", &chave).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(nomes(&body).is_empty());
+    assert_eq!(body["total"], 5);
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn parametros_fora_do_intervalo(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    solicitacoes(&pool, "app-a", 3).await;
+    let chave = chave(&pool, "app-a").await;
+    let router = router(pool);
+
+    // pagina zero ou negativa vira a primeira
+    for page in ["0", "-3"] {
+        let (status, body) = get(&router, &format!("/solicitacoes?per_page=1&page={page}"), &chave).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["page"], 1, "page={page}");
+        assert_eq!(nomes(&body), vec!["Pedido 2"], "page={page}");
+    }
+
+    // pagina enorme nao estoura o offset
+    let (status, body) = get(&router, &format!("/solicitacoes?per_page=100&page={}", i64::MAX), &chave).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert!(nomes(&body).is_empty());
+    assert_eq!(body["total"], 3);
+
+    // per_page fica entre 1 e 100
+    let (_, body) = get(&router, "/solicitacoes?per_page=0", &chave).await;
+    assert_eq!(body["per_page"], 1);
+    assert_eq!(nomes(&body).len(), 1);
+    let (_, body) = get(&router, "/solicitacoes?per_page=100000", &chave).await;
+    assert_eq!(body["per_page"], 100);
+    assert_eq!(nomes(&body).len(), 3);
+
+    for uri in ["/solicitacoes?page=abc", "/solicitacoes?per_page=1.5", "/pontos/nearby?lat=x&lon=1"] {
+        let (status, body) = get(&router, uri, &chave).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+        assert_eq!(body["code"], "bad_request", "{uri}");
+    }
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn total_respeita_os_filtros(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    solicitacoes(&pool, "app-a", 4).await;
+    sqlx::query("UPDATE solicitacoes SET municipio = 'Ubá' WHERE nome IN ('Pedido 0', 'Pedido 1')")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let chave = chave(&pool, "app-a").await;
+    let router = router(pool);
+
+    let (_, body) = get(&router, "/solicitacoes?municipio=ub%C3%A1&per_page=1", &chave).await;
+    assert_eq!(body["total"], 2);
+    assert_eq!(nomes(&body), vec!["Pedido 1"]);
+
+    // % digitado na busca e literal
+    let (_, body) = get(&router, "/solicitacoes?q=%25", &chave).await;
+    assert_eq!(body["total"], 0);
+    let (_, body) = get(&router, "/solicitacoes?q=pedido%203", &chave).await;
+    assert_eq!(nomes(&body), vec!["Pedido 3"]);
+}
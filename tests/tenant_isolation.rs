@@ -0,0 +1,275 @@
+// Isolamento entre apps (tenants) nas listagens e no acesso direto por uid
+//
+// Roda contra um banco temporario criado pelo sqlx::test a partir de migrations/;
+// precisa de DATABASE_URL apontando para um Postgres onde o usuario possa criar bancos.
+mod common;
+
+use axum::http::{Method, StatusCode};
+use serde_json::Value;
+use sqlx::PgPool;
+
+use emapi::denuncias::ConfigBloqueio;
+
+use common::{app, chave, enviar, get, post, registrar, router, router_com_bloqueio, usuario_com_papel, Credencial};
+
+async fn voluntario(pool: &PgPool, app_id: &str, telefone: &str) {
+    sqlx::query("INSERT INTO voluntarios (app_id, nome, telefone, mensagem) VALUES ($1, 'Voluntario', $2, 'posso ajudar')")
+        .bind(app_id)
+        .bind(telefone)
+        .execute(pool)
+        .await
+        .unwrap();
+}
+
+fn telefones(body: &Value) -> Vec<String> {
+    body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["telefone"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn app_nao_le_voluntarios_de_outro_app_privado(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    app(&pool, "app-b", "private", false).await;
+    voluntario(&pool, "app-a", "32990000001").await;
+    voluntario(&pool, "app-b", "32990000002").await;
+
+    let chave_a = chave(&pool, "app-a").await;
+    let router = router(pool);
+
+    let (status, body) = get(&router, "/voluntarios", &chave_a).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 1);
+    assert_eq!(telefones(&body), vec!["32990000001"]);
+
+    // filtrar pelo app alheio nao fura o escopo
+    let (_, body) = get(&router, "/voluntarios?app_id=app-b", &chave_a).await;
+    assert_eq!(body["total"], 0);
+    assert!(telefones(&body).is_empty());
+
+    // visao federada tambem nao inclui apps privados
+    let (_, body) = get(&router, "/voluntarios?federado=true", &chave_a).await;
+    assert!(!telefones(&body).contains(&"32990000002".to_string()));
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn visao_federada_inclui_apenas_apps_publicos_que_compartilham(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    app(&pool, "app-b", "private", true).await;
+    app(&pool, "app-publico", "public", true).await;
+    app(&pool, "app-publico-fechado", "public", false).await;
+    voluntario(&pool, "app-a", "32990000001").await;
+    voluntario(&pool, "app-b", "32990000002").await;
+    voluntario(&pool, "app-publico", "32990000003").await;
+    voluntario(&pool, "app-publico-fechado", "32990000004").await;
+
+    let chave_a = chave(&pool, "app-a").await;
+    let router = router(pool);
+
+    // sem opt-in do chamador, apenas o proprio app
+    let (_, body) = get(&router, "/voluntarios", &chave_a).await;
+    assert_eq!(telefones(&body), vec!["32990000001"]);
+
+    let (_, body) = get(&router, "/voluntarios?federado=true", &chave_a).await;
+    let mut vistos = telefones(&body);
+    vistos.sort();
+    assert_eq!(vistos, vec!["32990000001", "32990000003"]);
+    assert_eq!(body["total"], 2);
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn todas_as_listagens_respeitam_o_app(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    app(&pool, "app-b", "private", false).await;
+
+    sqlx::query("INSERT INTO pontos (app_id, nome, telefone, bairro, latitude, longitude) VALUES ('app-b', 'Ponto B', '32990000002', 'Centro', -21.76, -43.35)")
+        .execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO solicitacoes (app_id, nome, telefone, mensagem) VALUES ('app-b', 'Pedido B', '32990000002', 'preciso de agua')")
+        .execute(&pool).await.unwrap();
+    sqlx::query("INSERT INTO denuncias (app_id, url, memorando) VALUES ('app-b', 'https://golpe.exemplo', 'pix falso')")
+        .execute(&pool).await.unwrap();
+
+    let chave_a = chave(&pool, "app-a").await;
+    let chave_b = chave(&pool, "app-b").await;
+    let router = router(pool);
+
+    for uri in ["/pontos", "/solicitacoes", "/denuncias", "/pontos/nearby?lat=-21.76&lon=-43.35"] {
+        let (status, body) = get(&router, uri, &chave_a).await;
+        assert_eq!(status, StatusCode::OK, "{uri}");
+        assert_eq!(body["total"], 0, "{uri}");
+
+        let (_, body) = get(&router, uri, &chave_b).await;
+        assert_eq!(body["total"], 1, "{uri}");
+    }
+
+    for uri in ["/pontos.geojson", "/solicitacoes.geojson"] {
+        let (status, body) = get(&router, uri, &chave_a).await;
+        assert_eq!(status, StatusCode::OK, "{uri}");
+        assert!(body["features"].as_array().unwrap().is_empty(), "{uri}");
+    }
+}
+
+async fn uid(pool: &PgPool, sql: &str) -> String {
+    sqlx::query_scalar(sql).fetch_one(pool).await.unwrap()
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn recurso_de_outro_app_nao_existe(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    app(&pool, "app-b", "private", false).await;
+
+    let ponto = uid(&pool, "INSERT INTO pontos (app_id, nome, telefone, bairro) VALUES ('app-b', 'Ponto B', '32990000002', 'Centro') RETURNING uid").await;
+    let solicitacao = uid(&pool, "INSERT INTO solicitacoes (app_id, nome, telefone, mensagem) VALUES ('app-b', 'Pedido B', '32990000002', 'preciso de agua') RETURNING uid").await;
+    let denuncia = uid(&pool, "INSERT INTO denuncias (app_id, url, memorando) VALUES ('app-b', 'https://golpe.exemplo', 'pix falso') RETURNING uid").await;
+    let voluntario_b = uid(&pool, "INSERT INTO voluntarios (app_id, nome, telefone, mensagem) VALUES ('app-b', 'Voluntario', '32990000002', 'posso ajudar') RETURNING uid").await;
+
+    let chave_a = chave(&pool, "app-a").await;
+    let chave_b = chave(&pool, "app-b").await;
+    let router = router(pool.clone());
+
+    let leituras = [
+        format!("/pontos/{ponto}"),
+        format!("/pontos/{ponto}/itens"),
+        format!("/solicitacoes/{solicitacao}/historico"),
+        format!("/solicitacoes/{solicitacao}/candidatos"),
+    ];
+    for uri in &leituras {
+        let (status, body) = get(&router, uri, &chave_a).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{uri}");
+        assert_eq!(body["code"], "not_found", "{uri}");
+
+        let (status, _) = get(&router, uri, &chave_b).await;
+        assert_eq!(status, StatusCode::OK, "{uri}");
+    }
+
+    // listas filhas que so filtram pelo app: vazias, sem vazar nada
+    let filtradas = [
+        format!("/pontos/{ponto}/atribuicoes"),
+        format!("/solicitacoes/{solicitacao}/atribuicoes"),
+        format!("/denuncias/{denuncia}/moderacao"),
+        format!("/denuncias/{denuncia}/evidencias"),
+    ];
+    for uri in filtradas {
+        let (status, body) = get(&router, &uri, &chave_a).await;
+        assert_eq!(status, StatusCode::OK, "{uri}");
+        assert!(body.as_array().unwrap().is_empty(), "{uri}");
+    }
+
+    let escritas = [
+        (Method::PATCH, format!("/pontos/{ponto}"), Some(serde_json::json!({ "nome": "Invadido" }))),
+        (Method::PUT, format!("/pontos/{ponto}/itens/agua"), Some(serde_json::json!({ "aceitando": true }))),
+        (Method::POST, format!("/solicitacoes/{solicitacao}/status"), Some(serde_json::json!({ "status": "ATENDIDO" }))),
+        (Method::POST, format!("/denuncias/{denuncia}/status"), Some(serde_json::json!({ "status": "CONFIRMADA" }))),
+        (Method::DELETE, format!("/pontos/{ponto}"), None),
+    ];
+    for (metodo, uri, corpo) in escritas {
+        let (status, body) = enviar(&router, metodo.clone(), &uri, Credencial::Chave(&chave_a), corpo).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{metodo} {uri}: {body}");
+    }
+
+    // nada mudou no app dono
+    let (_, body) = get(&router, &format!("/pontos/{ponto}"), &chave_b).await;
+    assert_eq!(body["nome"], "Ponto B");
+    let (_, body) = get(&router, &format!("/solicitacoes/{solicitacao}/historico"), &chave_b).await;
+    assert!(body.as_array().unwrap().is_empty());
+    let itens: i64 = sqlx::query_scalar("SELECT count(*) FROM itens_pontos").fetch_one(&pool).await.unwrap();
+    assert_eq!(itens, 0);
+
+    // voluntario de outro app nao pode ser atribuido
+    let solicitacao_a = uid(&pool, "INSERT INTO solicitacoes (app_id, nome, telefone, mensagem) VALUES ('app-a', 'Pedido A', '32990000001', 'preciso de agua') RETURNING uid").await;
+    let (status, body) = post(&router, &format!("/solicitacoes/{solicitacao_a}/atribuicoes"), &chave_a, serde_json::json!({ "voluntario_uid": voluntario_b })).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "{body}");
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn usuario_so_enxerga_o_proprio_app(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    app(&pool, "app-b", "private", false).await;
+    let chave_a = chave(&pool, "app-a").await;
+    let chave_b = chave(&pool, "app-b").await;
+    let router = router(pool);
+
+    let id_b = registrar(&router, "b@exemplo.org", "app-b").await;
+    let token_a = usuario_com_papel(&router, &chave_a, "a@exemplo.org", "app-a", "ADMIN").await;
+
+    let (status, body) = enviar(&router, Method::GET, "/users", Credencial::Bearer(&token_a), None).await;
+    assert_eq!(status, StatusCode::OK);
+    let emails: Vec<&str> = body.as_array().unwrap().iter().map(|u| u["email"].as_str().unwrap()).collect();
+    assert_eq!(emails, vec!["a@exemplo.org"]);
+
+    // papel em usuario de outro app
+    let (status, _) = enviar(&router, Method::PUT, &format!("/users/{id_b}/papel"), Credencial::Chave(&chave_a), Some(serde_json::json!({ "papel": "ADMIN" }))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // o token de um app nao vale como credencial de outro: a chave manda
+    let (_, body) = enviar(&router, Method::GET, "/papeis", Credencial::Chave(&chave_b), None).await;
+    assert!(body.as_array().unwrap().is_empty(), "{body}");
+}
+
+async fn confirmar_denuncia(router: &axum::Router, chave: &str, pix: &str) {
+    let (status, denuncia) = post(router, "/denuncias", chave, serde_json::json!({ "tipo": "pix", "url": "https://golpe.exemplo", "chave_pix": pix })).await;
+    assert_eq!(status, StatusCode::OK, "{denuncia}");
+    let uri = format!("/denuncias/{}/status", denuncia["uid"].as_str().unwrap());
+    let (status, body) = post(router, &uri, chave, serde_json::json!({ "status": "CONFIRMADA" })).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn confirmacao_de_um_app_bloqueia_em_todos_por_padrao(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    app(&pool, "app-b", "private", false).await;
+    let chave_a = chave(&pool, "app-a").await;
+    let chave_b = chave(&pool, "app-b").await;
+    let router = router(pool);
+
+    let ponto = serde_json::json!({ "nome": "Golpe", "telefone": "32999990000", "bairro": "Centro", "pix": "golpista@exemplo.com" });
+    let (status, ponto_b) = post(&router, "/pontos", &chave_b, ponto.clone()).await;
+    assert_eq!(status, StatusCode::OK, "{ponto_b}");
+    let ponto_b = format!("/pontos/{}", ponto_b["uid"].as_str().unwrap());
+
+    confirmar_denuncia(&router, &chave_a, "golpista@exemplo.com").await;
+    let (_, body) = get(&router, &ponto_b, &chave_b).await;
+    assert!(body["bloqueado_em"].is_string(), "{body}");
+    let (status, _) = post(&router, "/pontos", &chave_b, ponto).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn minimo_de_apps_vale_so_para_pontos_de_outros_apps(pool: PgPool) {
+    app(&pool, "app-a", "private", false).await;
+    app(&pool, "app-b", "private", false).await;
+    app(&pool, "app-c", "private", false).await;
+    let chave_a = chave(&pool, "app-a").await;
+    let chave_b = chave(&pool, "app-b").await;
+    let chave_c = chave(&pool, "app-c").await;
+    let router = router_com_bloqueio(pool, ConfigBloqueio { min_apps: 2 });
+
+    let ponto = serde_json::json!({ "nome": "Entidade", "telefone": "32999990000", "bairro": "Centro", "pix": "contato@entidade.org" });
+    let (status, ponto_b) = post(&router, "/pontos", &chave_b, ponto.clone()).await;
+    assert_eq!(status, StatusCode::OK, "{ponto_b}");
+    let ponto_b = format!("/pontos/{}", ponto_b["uid"].as_str().unwrap());
+
+    // o app A sozinho bloqueia a chave so para si, mas a lista publica ja avisa os doadores
+    confirmar_denuncia(&router, &chave_a, "contato@entidade.org").await;
+    let (status, _) = post(&router, "/pontos", &chave_a, ponto.clone()).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (_, body) = get(&router, &ponto_b, &chave_b).await;
+    assert!(body["bloqueado_em"].is_null(), "{body}");
+    let (status, _) = post(&router, "/pontos", &chave_c, ponto.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, body) = enviar(&router, Method::GET, "/denuncias/blocklist", Credencial::Nenhuma, None).await;
+    assert_eq!(body["chaves_pix"], serde_json::json!(["contato@entidade.org"]));
+    let (_, body) = enviar(&router, Method::GET, "/pix/contato@entidade.org/status", Credencial::Nenhuma, None).await;
+    assert_eq!(body["bloqueada"], true);
+    assert_eq!(body["apps_que_confirmaram"], 1);
+
+    // confirmada por um segundo app, vale para os pontos de todos
+    confirmar_denuncia(&router, &chave_c, "contato@entidade.org").await;
+    let (_, body) = get(&router, &ponto_b, &chave_b).await;
+    assert!(body["bloqueado_em"].is_string(), "{body}");
+    let (_, body) = enviar(&router, Method::GET, "/pix/contato@entidade.org/status", Credencial::Nenhuma, None).await;
+    assert_eq!(body["apps_que_confirmaram"], 2);
+}